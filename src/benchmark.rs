@@ -1,42 +1,411 @@
-use serde_json::Value;
+use rand::Rng;
+use serde_json::{json, Value};
 
-use crate::core::{Game, GameStatus, Strategy};
-use crate::playground::{simulate, PlaygroundUtils};
+use crate::core::{Game, GameStatus, MatchResult, MoveRecord, Player, SearchTotals, Strategy};
+use crate::playground::{simulate_from, PlaygroundUtils};
+use crate::stats::{elo_ratings, Hypothesis, Outcome, Score, Sprt, Z_95};
 
+use std::collections::BTreeMap;
 use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
 
 pub struct PlayerStats {
-    avg_move_time: u128,
-    avg_win_move_count: u64,
-    num_wins: u64,
+    score: Score,
+    // Sum over games of the average time per move, in milliseconds
+    move_time: f64,
+    // Sum over won games of the number of moves
+    win_moves: u64,
+    search: SearchTotals,
 }
 
 impl PlayerStats {
     pub fn new() -> PlayerStats {
         return PlayerStats {
-            avg_move_time: 0,
-            avg_win_move_count: 0,
-            num_wins: 0,
+            score: Score::new(),
+            move_time: 0.0,
+            win_moves: 0,
+            search: SearchTotals::default(),
         };
     }
+
+    pub fn avg_move_time(&self) -> f64 {
+        if self.score.games() == 0 {
+            return 0.0;
+        }
+
+        return self.move_time / self.score.games() as f64;
+    }
+
+    pub fn avg_win_move_count(&self) -> f64 {
+        if self.score.wins == 0 {
+            return 0.0;
+        }
+
+        return self.win_moves as f64 / self.score.wins as f64;
+    }
+
+    fn add_game(
+        &mut self,
+        outcome: Outcome,
+        num_moves: u32,
+        time: Duration,
+        search: &SearchTotals,
+    ) {
+        self.score.add(outcome);
+        // A game can be over before both players moved when it starts from an opening
+        self.move_time += time.as_secs_f64() * 1000.0 / num_moves.max(1) as f64;
+        self.search.merge(search);
+
+        if outcome == Outcome::Win {
+            self.win_moves += num_moves as u64;
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        let (low, high) = self.score.wilson_interval(Z_95);
+
+        return json!({
+            "games": self.score.games(),
+            "wins": self.score.wins,
+            "draws": self.score.draws,
+            "losses": self.score.losses,
+            "score": self.score.ratio(),
+            "scoreLow": low,
+            "scoreHigh": high,
+            "avgMoveTimeMs": self.avg_move_time(),
+            "avgWinMoves": self.avg_win_move_count(),
+            "nodes": self.search.nodes,
+            "nodesPerSec": self.search.nodes_per_sec(),
+            "avgDepth": self.search.avg_depth(),
+            "playouts": self.search.playouts,
+            "ttHitRate": self.search.tt_hit_rate(),
+        });
+    }
+
+    fn write(&self, f: &mut fmt::Formatter<'_>, indent: &str) -> fmt::Result {
+        let (low, high) = self.score.wilson_interval(Z_95);
+
+        write!(f, "{0}Games:\t{1}\n{0}W/D/L:\t{2}/{3}/{4}\n{0}Score:\t{5:.1}% [{6:.1}%, {7:.1}%]\n{0}Avg Move Time:\t{8:.2}\n{0}Avg Win Moves:\t{9:.2}\n{0}Nodes:\t{10}\n{0}Nodes/sec:\t{11:.0}\n{0}Avg Depth:\t{12:.2}\n{0}Playouts:\t{13}\n{0}TT Hit Rate:\t{14:.3}\n",
+            indent,
+            self.score.games(),
+            self.score.wins,
+            self.score.draws,
+            self.score.losses,
+            self.score.ratio() * 100.0,
+            low * 100.0,
+            high * 100.0,
+            self.avg_move_time(),
+            self.avg_win_move_count(),
+            self.search.nodes,
+            self.search.nodes_per_sec(),
+            self.search.avg_depth(),
+            self.search.playouts,
+            self.search.tt_hit_rate())
+    }
+}
+
+fn outcome(status: &GameStatus, player: Player) -> Outcome {
+    return match (status, player) {
+        (GameStatus::Player1Win, Player::Player1) | (GameStatus::Player2Win, Player::Player2) => {
+            Outcome::Win
+        }
+        (GameStatus::Player1Win, _) | (GameStatus::Player2Win, _) => Outcome::Loss,
+        _ => Outcome::Draw,
+    };
+}
+
+// Results of one of the two strategies of a test, whichever seat it played from
+pub struct StrategyStats {
+    name: String,
+    overall: PlayerStats,
+    as_player1: PlayerStats,
+    as_player2: PlayerStats,
+}
+
+impl StrategyStats {
+    pub fn new(name: &str) -> StrategyStats {
+        return StrategyStats {
+            name: name.to_string(),
+            overall: PlayerStats::new(),
+            as_player1: PlayerStats::new(),
+            as_player2: PlayerStats::new(),
+        };
+    }
+
+    fn add_game(
+        &mut self,
+        player: Player,
+        status: &GameStatus,
+        num_moves: u32,
+        time: Duration,
+        search: &SearchTotals,
+    ) {
+        let outcome = outcome(status, player);
+        let seat = match player {
+            Player::Player1 => &mut self.as_player1,
+            Player::Player2 => &mut self.as_player2,
+        };
+
+        seat.add_game(outcome, num_moves, time, search);
+        self.overall.add_game(outcome, num_moves, time, search);
+    }
+
+    fn to_json(&self) -> Value {
+        return json!({
+            "name": self.name,
+            "overall": self.overall.to_json(),
+            "asPlayer1": self.as_player1.to_json(),
+            "asPlayer2": self.as_player2.to_json(),
+        });
+    }
+}
+
+// Enough to replay a single game: run the same test with this seed and one iteration
+pub struct GameRecord {
+    pub seed: u64,
+    // The second strategy played as Player 1
+    pub swapped: bool,
+    pub status: GameStatus,
+    pub num_moves: u32,
+    pub player1_time: Duration,
+    pub player2_time: Duration,
+    pub player1_search: SearchTotals,
+    pub player2_search: SearchTotals,
+    pub termination: String,
+    pub opening: Option<String>,
+    pub moves: Vec<MoveRecord>,
+    // The game in its standard record format, PGN for chess
+    pub export: Option<String>,
+}
+
+// Columns of the rows written by BenchmarkResult::csv_rows, one row per game
+pub const CSV_HEADER: &str = "label,strategy1,strategy2,game,seed,swapped,opening,status,termination,strategy1_score,moves,p1_time_ms,p2_time_ms,p1_nodes,p2_nodes,p1_avg_depth,p2_avg_depth,p1_playouts,p2_playouts,p1_tt_hit_rate,p2_tt_hit_rate";
+
+fn csv_field(s: &str) -> String {
+    if s.contains(',') || s.contains('"') || s.contains('\n') {
+        return format!("\"{}\"", s.replace('"', "\"\""));
+    }
+
+    return s.to_string();
+}
+
+fn status_name(status: &GameStatus) -> &'static str {
+    return match status {
+        GameStatus::Player1Win => "player1",
+        GameStatus::Player2Win => "player2",
+        GameStatus::Draw => "draw",
+        GameStatus::InProgress => "in-progress",
+    };
+}
+
+impl GameRecord {
+    // Points scored by the first strategy of the test
+    fn strategy1_score(&self) -> f64 {
+        let seat = if self.swapped {
+            Player::Player2
+        } else {
+            Player::Player1
+        };
+
+        return match outcome(&self.status, seat) {
+            Outcome::Win => 1.0,
+            Outcome::Draw => 0.5,
+            Outcome::Loss => 0.0,
+        };
+    }
+
+    fn to_json(&self) -> Value {
+        return json!({
+            "seed": self.seed,
+            "swapped": self.swapped,
+            "status": status_name(&self.status),
+            "termination": self.termination,
+            "opening": self.opening,
+            "strategy1Score": self.strategy1_score(),
+            "numMoves": self.num_moves,
+            "moves": self.moves.iter().map(|m| json!({
+                "player": match m.player {
+                    Player::Player1 => 1,
+                    Player::Player2 => 2,
+                },
+                "action": m.action,
+                "timeMs": m.time.as_secs_f64() * 1000.0,
+            })).collect::<Vec<Value>>(),
+            "player1": {
+                "timeMs": self.player1_time.as_secs_f64() * 1000.0,
+                "nodes": self.player1_search.nodes,
+                "avgDepth": self.player1_search.avg_depth(),
+                "playouts": self.player1_search.playouts,
+                "ttHitRate": self.player1_search.tt_hit_rate(),
+            },
+            "player2": {
+                "timeMs": self.player2_time.as_secs_f64() * 1000.0,
+                "nodes": self.player2_search.nodes,
+                "avgDepth": self.player2_search.avg_depth(),
+                "playouts": self.player2_search.playouts,
+                "ttHitRate": self.player2_search.tt_hit_rate(),
+            },
+        });
+    }
 }
 
 pub struct BenchmarkResult {
+    label: String,
+    // Configured iterations, with swapped colors every one of them is a pair of games. The
+    // games actually played are in games, an SPRT may stop before all of them are.
     match_count: u64,
-    p1_stats: PlayerStats,
-    p2_stats: PlayerStats,
+    seed: u64,
+    strategy1: StrategyStats,
+    strategy2: StrategyStats,
+    games: Vec<GameRecord>,
+    sprt: Option<(Sprt, Option<Hypothesis>)>,
+}
+
+impl BenchmarkResult {
+    pub fn to_json(&self) -> Value {
+        let score = &self.strategy1.overall.score;
+        let (elo_low, elo_high) = score.elo_interval(Z_95);
+
+        return json!({
+            "label": self.label,
+            "matchCount": self.match_count,
+            "gamesPlayed": self.games.len(),
+            "seed": self.seed,
+            "strategies": [self.strategy1.to_json(), self.strategy2.to_json()],
+            "eloDiff": score.elo(),
+            "eloLow": elo_low,
+            "eloHigh": elo_high,
+            "los": score.los(),
+            "sprt": self.sprt.map(|(sprt, decision)| json!({
+                "elo0": sprt.elo0,
+                "elo1": sprt.elo1,
+                "alpha": sprt.alpha,
+                "beta": sprt.beta,
+                "llr": sprt.llr(score),
+                "result": match decision {
+                    Some(Hypothesis::H0) => "H0",
+                    Some(Hypothesis::H1) => "H1",
+                    None => "inconclusive",
+                },
+            })),
+            "games": self.games.iter().map(|g| g.to_json()).collect::<Vec<Value>>(),
+        });
+    }
+
+    // Records of every game that the game can export, in the order they were played
+    pub fn exports(&self) -> Vec<&str> {
+        return self
+            .games
+            .iter()
+            .filter_map(|g| g.export.as_deref())
+            .collect();
+    }
+
+    // One line per game, see CSV_HEADER
+    pub fn csv_rows(&self) -> String {
+        let mut rows = String::new();
+
+        for (i, game) in self.games.iter().enumerate() {
+            rows.push_str(&format!(
+                "{},{},{},{},{},{},{},{},{},{},{},{:.3},{:.3},{},{},{:.2},{:.2},{},{},{:.3},{:.3}\n",
+                csv_field(&self.label),
+                csv_field(&self.strategy1.name),
+                csv_field(&self.strategy2.name),
+                i,
+                game.seed,
+                game.swapped,
+                csv_field(game.opening.as_deref().unwrap_or("")),
+                status_name(&game.status),
+                csv_field(&game.termination),
+                game.strategy1_score(),
+                game.num_moves,
+                game.player1_time.as_secs_f64() * 1000.0,
+                game.player2_time.as_secs_f64() * 1000.0,
+                game.player1_search.nodes,
+                game.player2_search.nodes,
+                game.player1_search.avg_depth(),
+                game.player2_search.avg_depth(),
+                game.player1_search.playouts,
+                game.player2_search.playouts,
+                game.player1_search.tt_hit_rate(),
+                game.player2_search.tt_hit_rate()
+            ));
+        }
+
+        return rows;
+    }
 }
 
 impl fmt::Display for BenchmarkResult {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Match count:\t{}\nPlayer 1:\n\tAvg Move Time:\t{}\n\tAvg Win Moves:\t{}\n\tNum Wins:\t{}\nPlayer 2:\n\tAvg Move Time:\t{}\n\tAvg Win Moves:\t{}\n\tNum Wins:\t{}\n",
+        write!(
+            f,
+            "Label:\t{}\nMatch count:\t{}\nGames played:\t{}\nSeed:\t{}\n",
+            self.label,
             self.match_count,
-            self.p1_stats.avg_move_time,
-            self.p1_stats.avg_win_move_count,
-            self.p1_stats.num_wins,
-            self.p2_stats.avg_move_time,
-            self.p2_stats.avg_win_move_count,
-            self.p2_stats.num_wins)
+            self.games.len(),
+            self.seed
+        )?;
+
+        for (i, strat) in [&self.strategy1, &self.strategy2].iter().enumerate() {
+            writeln!(f, "Strategy {} ({}):", i + 1, strat.name)?;
+            strat.overall.write(f, "\t")?;
+            writeln!(f, "\tAs Player 1:")?;
+            strat.as_player1.write(f, "\t\t")?;
+            writeln!(f, "\tAs Player 2:")?;
+            strat.as_player2.write(f, "\t\t")?;
+        }
+
+        // Strategy 1's score decides the comparison, strategy 2's is its mirror image
+        let score = &self.strategy1.overall.score;
+        let (elo_low, elo_high) = score.elo_interval(Z_95);
+        write!(
+            f,
+            "Strategy 1 vs Strategy 2:\n\tElo Diff:\t{:+.1} [{:+.1}, {:+.1}]\n\tLOS:\t{:.1}%\n",
+            score.elo(),
+            elo_low,
+            elo_high,
+            score.los() * 100.0
+        )?;
+
+        if let Some((sprt, decision)) = &self.sprt {
+            let (lower, upper) = sprt.bounds();
+            let verdict = match decision {
+                Some(Hypothesis::H0) => "H0 accepted",
+                Some(Hypothesis::H1) => "H1 accepted",
+                None => "Inconclusive",
+            };
+            write!(
+                f,
+                "SPRT (elo0 {}, elo1 {}, alpha {}, beta {}):\n\tResult:\t{}\n\tLLR:\t{:.2} [{:.2}, {:.2}]\n",
+                sprt.elo0,
+                sprt.elo1,
+                sprt.alpha,
+                sprt.beta,
+                verdict,
+                sprt.llr(score),
+                lower,
+                upper
+            )?;
+        }
+
+        writeln!(f, "Games:")?;
+        for (i, game) in self.games.iter().enumerate() {
+            writeln!(
+                f,
+                "\t{}\tSeed: {}{}\t{}\t{} moves",
+                i,
+                game.seed,
+                if game.swapped { "\tSwapped" } else { "" },
+                game.status,
+                game.num_moves
+            )?;
+        }
+
+        return Ok(());
     }
 }
 
@@ -57,6 +426,7 @@ fn select_strategy<T: Game + PlaygroundUtils>(
 
 pub trait BenchmarkGame {
     fn run(&self, test: &Test) -> Result<BenchmarkResult, &'static str>;
+    fn run_tournament(&self, tournament: &Tournament) -> Result<TournamentResult, &'static str>;
 }
 
 pub struct Test {
@@ -65,62 +435,460 @@ pub struct Test {
     pub p1_strat_conf: Value,
     pub p2_strat_conf: Value,
     pub iterations: u64,
+    // Number of games played at the same time
+    pub threads: usize,
+    // Game i is played with seed + i, a random seed is picked when None
+    pub seed: Option<u64>,
+    // Play every game twice, the second time with the strategies trading seats. Iterations
+    // then counts pairs of games.
+    pub swap_colors: bool,
+    // Stop as soon as the test decides between its hypotheses, iterations is then only an
+    // upper bound
+    pub sprt: Option<Sprt>,
+    // File the game records are written to, see PlaygroundUtils::export_match
+    pub export: Option<String>,
+    // Positions games start from, picked by game seed. Empty to start from the initial
+    // position.
+    pub openings: Vec<String>,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum TournamentFormat {
+    // Every strategy plays every other one
+    RoundRobin,
+    // The first strategy plays every other one
+    Gauntlet,
+}
+
+// Matches between many strategies, each pairing is played like a test with the same settings
+pub struct Tournament {
+    pub label: String,
+    pub game: Box<dyn BenchmarkGame>,
+    pub format: TournamentFormat,
+    pub strat_confs: Vec<Value>,
+    pub iterations: u64,
+    pub threads: usize,
+    pub seed: Option<u64>,
+    pub swap_colors: bool,
+    pub export: Option<String>,
+    pub openings: Vec<String>,
+}
+
+pub struct TournamentResult {
+    label: String,
+    seed: u64,
+    names: Vec<String>,
+    // Score of the row strategy against the column strategy, None if they didn't play
+    crosstable: Vec<Vec<Option<Score>>>,
+    ratings: Vec<f64>,
+    matches: Vec<BenchmarkResult>,
+}
+
+impl TournamentResult {
+    pub fn to_json(&self) -> Value {
+        let crosstable: Vec<Vec<Value>> = self
+            .crosstable
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| match cell {
+                        Some(score) => json!({
+                            "wins": score.wins,
+                            "draws": score.draws,
+                            "losses": score.losses,
+                        }),
+                        None => Value::Null,
+                    })
+                    .collect()
+            })
+            .collect();
+
+        return json!({
+            "label": self.label,
+            "seed": self.seed,
+            "strategies": self.names,
+            "ratings": self.ratings,
+            "crosstable": crosstable,
+            "matches": self.matches.iter().map(|m| m.to_json()).collect::<Vec<Value>>(),
+        });
+    }
+
+    pub fn exports(&self) -> Vec<&str> {
+        return self.matches.iter().flat_map(|m| m.exports()).collect();
+    }
+
+    // Games of every pairing, see CSV_HEADER
+    pub fn csv_rows(&self) -> String {
+        return self.matches.iter().map(|m| m.csv_rows()).collect();
+    }
+}
+
+impl fmt::Display for TournamentResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Label:\t{}\nSeed:\t{}\nCrosstable:",
+            self.label, self.seed
+        )?;
+
+        write!(f, "\t")?;
+        for i in 0..self.names.len() {
+            write!(f, "\t{}", i + 1)?;
+        }
+        writeln!(f)?;
+
+        for (i, row) in self.crosstable.iter().enumerate() {
+            write!(f, "\t{}. {}", i + 1, self.names[i])?;
+            for cell in row {
+                match cell {
+                    Some(score) => write!(f, "\t{}/{}", score.points(), score.games())?,
+                    None => write!(f, "\t-")?,
+                }
+            }
+            writeln!(f)?;
+        }
+
+        let mut ranking: Vec<usize> = (0..self.names.len()).collect();
+        ranking.sort_by(|&a, &b| self.ratings[b].partial_cmp(&self.ratings[a]).unwrap());
+
+        writeln!(f, "Ratings:\n\tRank\tElo\tScore\tW/D/L\tStrategy")?;
+        for (rank, &i) in ranking.iter().enumerate() {
+            let mut total = Score::new();
+            for score in self.crosstable[i].iter().flatten() {
+                total.merge(score);
+            }
+
+            writeln!(
+                f,
+                "\t{}\t{:+.1}\t{:.1}%\t{}/{}/{}\t{}. {}",
+                rank + 1,
+                self.ratings[i],
+                total.ratio() * 100.0,
+                total.wins,
+                total.draws,
+                total.losses,
+                i + 1,
+                self.names[i]
+            )?;
+        }
+
+        return Ok(());
+    }
+}
+
+// Outcomes of finished games that are tallied in order, so that an SPRT stops after the
+// same games however the workers are scheduled
+struct SprtProgress {
+    pending: BTreeMap<u64, Outcome>,
+    tallied: u64,
+    score: Score,
+}
+
+// Derives independent strategy seeds from a game seed (splitmix64 finalizer)
+fn mix_seed(seed: u64, stream: u64) -> u64 {
+    let mut z = seed ^ stream.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    return z ^ (z >> 31);
+}
+
+// A "seed" in a strategy's own config, mixed into every game seed of that strategy
+fn own_seed(conf: &Value) -> Result<Option<u64>, &'static str> {
+    return match &conf["seed"] {
+        Value::Null => Ok(None),
+        v => v
+            .as_u64()
+            .map(Some)
+            .ok_or("seed must be a positive integer"),
+    };
+}
+
+// Seed a strategy plays a game with. Without a seed of its own it only depends on the game
+// seed, so either way a single game can be replayed from its recorded seed.
+fn strategy_seed(game_seed: u64, stream: u64, own_seed: Option<u64>) -> u64 {
+    let seed = mix_seed(game_seed, stream);
+
+    return match own_seed {
+        Some(own_seed) => mix_seed(seed, own_seed),
+        None => seed,
+    };
+}
+
+fn strategy_name(conf: &Value) -> Result<&str, &'static str> {
+    return match &conf["name"] {
+        Value::String(s) => Ok(s),
+        _ => Err("name must be a string"),
+    };
+}
+
+// Tells apart tournament entries that use the same strategy with different settings
+fn participant_name(conf: &Value) -> Result<String, &'static str> {
+    return match &conf["label"] {
+        Value::String(s) => Ok(s.clone()),
+        _ => Ok(strategy_name(conf)?.to_string()),
+    };
+}
+
+// How the games of a match are played, shared by tests and tournaments
+struct Schedule {
+    iterations: u64,
+    threads: usize,
+    seed: u64,
+    swap_colors: bool,
+    sprt: Option<Sprt>,
+    openings: Vec<String>,
+}
+
+fn play_match<T: Game + PlaygroundUtils + Sync>(
+    game: &T,
+    label: &str,
+    conf1: &Value,
+    conf2: &Value,
+    schedule: &Schedule,
+) -> Result<BenchmarkResult, &'static str> {
+    let name1 = strategy_name(conf1)?;
+    let name2 = strategy_name(conf2)?;
+    let own_seed1 = own_seed(conf1)?;
+    let own_seed2 = own_seed(conf2)?;
+    let games_per_iteration = if schedule.swap_colors { 2 } else { 1 };
+    let num_games = schedule.iterations * games_per_iteration;
+    let seed = schedule.seed;
+    let next_game = AtomicU64::new(0);
+    let sprt = schedule.sprt;
+
+    // Parsed up front so that a bad book fails before any game is played
+    let openings = schedule
+        .openings
+        .iter()
+        .map(|opening| match game.parse_state(opening) {
+            Some(state) if game.status(&state) == GameStatus::InProgress => Ok(state),
+            Some(_) => Err("Opening position is already decided"),
+            None => Err("Invalid opening position"),
+        })
+        .collect::<Result<Vec<T::State>, &'static str>>()?;
+    let game_limit = AtomicU64::new(num_games);
+    let progress = Mutex::new(SprtProgress {
+        pending: BTreeMap::new(),
+        tallied: 0,
+        score: Score::new(),
+    });
+
+    // Every worker plays with its own strategies and takes the next game to be played
+    // until none are left
+    let mut results: Vec<(u64, MatchResult)> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..schedule.threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let strat1 = select_strategy(game, name1).expect("No matching strategy");
+                    let strat2 = select_strategy(game, name2).expect("No matching strategy");
+
+                    strat1.configure(conf1);
+                    strat2.configure(conf2);
+
+                    let mut results = Vec::new();
+
+                    loop {
+                        let idx = next_game.fetch_add(1, Ordering::SeqCst);
+
+                        if idx >= game_limit.load(Ordering::SeqCst) {
+                            return results;
+                        }
+
+                        // Reseeding before every game makes it independent of which
+                        // worker plays it and of the games played before. Both games of
+                        // a pair share a seed. A seed in the strategy's own config is mixed
+                        // in rather than overridden.
+                        let game_seed = seed.wrapping_add(idx / games_per_iteration);
+                        strat1.seed(strategy_seed(game_seed, 1, own_seed1));
+                        strat2.seed(strategy_seed(game_seed, 2, own_seed2));
+
+                        // Both games of a pair also start from the same opening
+                        let opening = if openings.is_empty() {
+                            None
+                        } else {
+                            Some((mix_seed(game_seed, 3) % openings.len() as u64) as usize)
+                        };
+                        let state = match opening {
+                            Some(i) => openings[i].clone(),
+                            None => game.init(),
+                        };
+
+                        let swapped = idx % games_per_iteration == 1;
+                        let mut result = if swapped {
+                            simulate_from(game, state, &(*strat2), &(*strat1), false)
+                        } else {
+                            simulate_from(game, state, &(*strat1), &(*strat2), false)
+                        };
+                        result.opening = opening.map(|i| schedule.openings[i].clone());
+
+                        if let Some(sprt) = &sprt {
+                            let seat = if swapped {
+                                Player::Player2
+                            } else {
+                                Player::Player1
+                            };
+                            let mut guard = progress.lock().unwrap();
+                            let progress = &mut *guard;
+                            progress.pending.insert(idx, outcome(&result.status, seat));
+
+                            while let Some(o) = progress.pending.remove(&progress.tallied) {
+                                progress.score.add(o);
+                                progress.tallied += 1;
+
+                                // Only stop after complete pairs of games
+                                if progress.tallied.is_multiple_of(games_per_iteration)
+                                    && sprt.decide(&progress.score).is_some()
+                                {
+                                    game_limit.fetch_min(progress.tallied, Ordering::SeqCst);
+                                }
+                            }
+                        }
+
+                        results.push((idx, result));
+                    }
+                })
+            })
+            .collect();
+
+        return handles
+            .into_iter()
+            .flat_map(|h| h.join().expect("Benchmark worker panicked"))
+            .collect();
+    });
+
+    // Tally games in the order they were scheduled so the result doesn't depend on
+    // which worker finished first
+    // Games that were started after the SPRT finished don't count
+    let game_limit = game_limit.into_inner();
+    results.retain(|(idx, _)| *idx < game_limit);
+    results.sort_by_key(|(idx, _)| *idx);
+
+    let mut strategy1 = StrategyStats::new(&participant_name(conf1)?);
+    let mut strategy2 = StrategyStats::new(&participant_name(conf2)?);
+    let mut games = Vec::new();
+
+    for (idx, result) in results {
+        let swapped = idx % games_per_iteration == 1;
+        let (player1, player2) = if swapped {
+            (&mut strategy2, &mut strategy1)
+        } else {
+            (&mut strategy1, &mut strategy2)
+        };
+
+        player1.add_game(
+            Player::Player1,
+            &result.status,
+            result.num_moves,
+            result.player1_time,
+            &result.player1_search,
+        );
+        player2.add_game(
+            Player::Player2,
+            &result.status,
+            result.num_moves,
+            result.player2_time,
+            &result.player2_search,
+        );
+
+        let export = game.export_match(&result, label, idx + 1, &player1.name, &player2.name);
+
+        games.push(GameRecord {
+            seed: seed.wrapping_add(idx / games_per_iteration),
+            swapped: swapped,
+            status: result.status,
+            num_moves: result.num_moves,
+            player1_time: result.player1_time,
+            player2_time: result.player2_time,
+            player1_search: result.player1_search,
+            player2_search: result.player2_search,
+            termination: result.termination,
+            opening: result.opening,
+            moves: result.moves,
+            export: export,
+        });
+    }
+
+    let sprt = sprt.map(|sprt| (sprt, sprt.decide(&strategy1.overall.score)));
+
+    return Ok(BenchmarkResult {
+        label: label.to_string(),
+        match_count: schedule.iterations,
+        seed: seed,
+        strategy1: strategy1,
+        strategy2: strategy2,
+        games: games,
+        sprt: sprt,
+    });
 }
 
 impl<T> BenchmarkGame for T
 where
-    T: Game + PlaygroundUtils,
+    T: Game + PlaygroundUtils + Sync,
 {
     fn run(&self, test: &Test) -> Result<BenchmarkResult, &'static str> {
-        let p1_strat = match &test.p1_strat_conf["name"] {
-            Value::String(s) => select_strategy(self, &s).expect("No matching strategy"),
-            _ => return Err("name must be a string"),
+        let schedule = Schedule {
+            iterations: test.iterations,
+            threads: test.threads,
+            seed: test.seed.unwrap_or_else(|| rand::thread_rng().gen()),
+            swap_colors: test.swap_colors,
+            sprt: test.sprt,
+            openings: test.openings.clone(),
         };
-        let p2_strat = match &test.p2_strat_conf["name"] {
-            Value::String(s) => select_strategy(self, &s).expect("No matching strategy"),
-            _ => return Err("name must be a string"),
+
+        return play_match(
+            self,
+            &test.label,
+            &test.p1_strat_conf,
+            &test.p2_strat_conf,
+            &schedule,
+        );
+    }
+
+    fn run_tournament(&self, tournament: &Tournament) -> Result<TournamentResult, &'static str> {
+        let confs = &tournament.strat_confs;
+        let names = confs
+            .iter()
+            .map(participant_name)
+            .collect::<Result<Vec<String>, &'static str>>()?;
+        let schedule = Schedule {
+            iterations: tournament.iterations,
+            threads: tournament.threads,
+            seed: tournament.seed.unwrap_or_else(|| rand::thread_rng().gen()),
+            swap_colors: tournament.swap_colors,
+            sprt: None,
+            openings: tournament.openings.clone(),
         };
 
-        p1_strat.configure(&test.p1_strat_conf);
-        p2_strat.configure(&test.p2_strat_conf);
-
-        let mut p1_stats = PlayerStats::new();
-        let mut p2_stats = PlayerStats::new();
-
-        for iter in 0..test.iterations {
-            let result = simulate(self, &(*p1_strat), &(*p2_strat), false);
-            let p1_avg_move_time = result.player1_time.as_millis() / result.num_moves as u128;
-            let p2_avg_move_time = result.player2_time.as_millis() / result.num_moves as u128;
-
-            let i = iter as u128;
-            p1_stats.avg_move_time = ((p1_stats.avg_move_time * i) + p1_avg_move_time) / (i + 1);
-            p2_stats.avg_move_time = ((p2_stats.avg_move_time * i) + p2_avg_move_time) / (i + 1);
-
-            match result.status {
-                GameStatus::Player1Win => {
-                    let new_win_count = p1_stats.num_wins + 1;
-                    p1_stats.avg_win_move_count = ((p1_stats.avg_win_move_count
-                        * p1_stats.num_wins)
-                        + result.num_moves as u64)
-                        / new_win_count;
-                    p1_stats.num_wins = new_win_count;
-                }
-                GameStatus::Player2Win => {
-                    let new_win_count = p2_stats.num_wins + 1;
-                    p2_stats.avg_win_move_count = ((p2_stats.avg_win_move_count
-                        * p2_stats.num_wins)
-                        + result.num_moves as u64)
-                        / new_win_count;
-                    p2_stats.num_wins = new_win_count;
-                }
-                _ => {}
-            }
+        let pairings: Vec<(usize, usize)> = match tournament.format {
+            TournamentFormat::RoundRobin => (0..confs.len())
+                .flat_map(|a| (a + 1..confs.len()).map(move |b| (a, b)))
+                .collect(),
+            TournamentFormat::Gauntlet => (1..confs.len()).map(|b| (0, b)).collect(),
+        };
+
+        let mut crosstable = vec![vec![None; confs.len()]; confs.len()];
+        let mut scores = Vec::new();
+        let mut matches = Vec::new();
+
+        for (a, b) in pairings {
+            let label = format!("{}: {} vs {}", tournament.label, names[a], names[b]);
+            let result = play_match(self, &label, &confs[a], &confs[b], &schedule)?;
+            let a_score = result.strategy1.overall.score;
+            let b_score = result.strategy2.overall.score;
+
+            crosstable[a][b] = Some(a_score);
+            crosstable[b][a] = Some(b_score);
+            scores.push((a, b, a_score));
+            matches.push(result);
         }
 
-        return Ok(BenchmarkResult {
-            match_count: test.iterations,
-            p1_stats: p1_stats,
-            p2_stats: p2_stats,
+        return Ok(TournamentResult {
+            label: tournament.label.clone(),
+            seed: schedule.seed,
+            names: names,
+            crosstable: crosstable,
+            ratings: elo_ratings(confs.len(), &scores),
+            matches: matches,
         });
     }
 }
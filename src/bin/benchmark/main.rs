@@ -1,66 +1,354 @@
-use board_game_ai::benchmark::{BenchmarkGame, Test};
+use board_game_ai::benchmark::{BenchmarkGame, Test, Tournament, TournamentFormat, CSV_HEADER};
 use board_game_ai::games;
+use board_game_ai::stats::Sprt;
 
 use serde_json::Value;
 
 use std::env;
 use std::fs;
 
-fn read_game(name: &str) -> Option<Box<dyn BenchmarkGame>> {
+// Board dimension of an m,n,k-game, default if it isn't set
+fn read_size(v: &Value, default: usize) -> Result<usize, &'static str> {
+    return match v {
+        Value::Null => Ok(default),
+        _ => v
+            .as_u64()
+            .map(|size| size as usize)
+            .ok_or("m, n and k must be positive integers"),
+    };
+}
+
+// A game is either its name or an object with the name and the game's settings
+fn read_game(v: &Value) -> Result<Box<dyn BenchmarkGame>, &'static str> {
+    let name = match v {
+        Value::String(s) => s.as_str(),
+        Value::Object(_) => v["name"].as_str().ok_or("Game name must be a string")?,
+        _ => return Err("Game must be a string or an object"),
+    };
+
     return match name {
-        "chess" => Some(Box::new(games::Chess {})),
-        "tic-tac-toe" => Some(Box::new(games::TicTacToe {})),
-        _ => None,
+        "chess" => {
+            let mut chess = games::Chess::new();
+            match &v["maxMoves"] {
+                Value::Null => {}
+                max => {
+                    let max = max.as_u64().ok_or("maxMoves must be a positive integer")?;
+                    chess = chess.with_max_moves(max as u32);
+                }
+            }
+            Ok(Box::new(chess))
+        }
+        "tic-tac-toe" | "gomoku" | "mnk" => {
+            // The presets' board and line length can be overridden like those of "mnk"
+            let preset = match name {
+                "gomoku" => games::MnkGame::gomoku(),
+                _ => games::MnkGame::tic_tac_toe(),
+            };
+            let m = read_size(&v["m"], preset.m)?;
+            let n = read_size(&v["n"], preset.n)?;
+            let k = read_size(&v["k"], preset.k)?;
+
+            if !(1..=games::MAX_WIDTH).contains(&m) || n < 1 {
+                return Err("m must be between 1 and 26 and n at least 1");
+            }
+            if m.checked_mul(n)
+                .is_none_or(|cells| cells > games::MAX_CELLS)
+            {
+                return Err("m * n must be at most 65536");
+            }
+            if k < 1 || k > m.max(n) {
+                return Err("k must be between 1 and the longer side");
+            }
+            Ok(Box::new(games::MnkGame::new(m, n, k)))
+        }
+        "connect-four" => Ok(Box::new(games::ConnectFour {})),
+        "othello" => Ok(Box::new(games::Othello {})),
+        "go" => {
+            let mut go = games::Go::new();
+            match &v["size"] {
+                Value::Null => {}
+                size => {
+                    let size = size.as_u64().ok_or("size must be a positive integer")?;
+                    if size < 2 || size > games::MAX_SIZE as u64 {
+                        return Err("size must be between 2 and 19");
+                    }
+                    go = go.with_size(size as usize);
+                }
+            }
+            match &v["komi"] {
+                Value::Null => {}
+                komi => go = go.with_komi(komi.as_f64().ok_or("komi must be a number")?),
+            }
+            match &v["maxMoves"] {
+                Value::Null => {}
+                max => {
+                    let max = max.as_u64().ok_or("maxMoves must be a positive integer")?;
+                    go = go.with_max_moves(max as u32);
+                }
+            }
+            Ok(Box::new(go))
+        }
+        _ => Err("Unknown game"),
+    };
+}
+
+fn read_sprt(v: &Value) -> Result<Option<Sprt>, &'static str> {
+    if v.is_null() {
+        return Ok(None);
+    }
+
+    let elo0 = v["elo0"].as_f64().ok_or("sprt.elo0 must be a number")?;
+    let elo1 = v["elo1"].as_f64().ok_or("sprt.elo1 must be a number")?;
+    let alpha = match &v["alpha"] {
+        Value::Null => 0.05,
+        a => a.as_f64().ok_or("sprt.alpha must be a number")?,
+    };
+    let beta = match &v["beta"] {
+        Value::Null => 0.05,
+        b => b.as_f64().ok_or("sprt.beta must be a number")?,
+    };
+
+    if elo1 <= elo0 {
+        return Err("sprt.elo1 must be greater than sprt.elo0");
+    }
+    if alpha <= 0.0 || alpha >= 1.0 || beta <= 0.0 || beta >= 1.0 {
+        return Err("sprt.alpha and sprt.beta must be between 0 and 1");
+    }
+
+    return Ok(Some(Sprt::new(elo0, elo1, alpha, beta)));
+}
+
+fn read_iterations(v: &Value) -> Result<u64, &'static str> {
+    return match v {
+        Value::Number(n) => {
+            if n.is_u64() {
+                Ok(n.as_u64().unwrap())
+            } else {
+                Err("Iterations must be a positive integer")
+            }
+        }
+        _ => Err("Iterations must be a number"),
+    };
+}
+
+fn read_threads(v: &Value) -> Result<usize, &'static str> {
+    return match v {
+        Value::Null => Ok(1),
+        Value::Number(n) => match n.as_u64() {
+            Some(t) if t > 0 => Ok(t as usize),
+            _ => Err("Threads must be a positive integer"),
+        },
+        _ => Err("Threads must be a number"),
+    };
+}
+
+fn read_seed(v: &Value) -> Result<Option<u64>, &'static str> {
+    return match v {
+        Value::Null => Ok(None),
+        Value::Number(n) => match n.as_u64() {
+            Some(seed) => Ok(Some(seed)),
+            None => Err("Seed must be a non-negative integer"),
+        },
+        _ => Err("Seed must be a number"),
+    };
+}
+
+fn read_swap_colors(v: &Value) -> Result<bool, &'static str> {
+    return match v {
+        Value::Null => Ok(false),
+        Value::Bool(b) => Ok(*b),
+        _ => Err("swapColors must be a boolean"),
+    };
+}
+
+fn read_export(v: &Value) -> Result<Option<String>, &'static str> {
+    return match v {
+        Value::Null => Ok(None),
+        Value::String(s) => Ok(Some(s.clone())),
+        _ => Err("Export must be a file name"),
     };
 }
 
-fn load_tests(v: Value) -> Result<Vec<Test>, &'static str> {
+// A single "opening" position and/or an "openings" file with one position per line
+fn read_openings(td: &Value) -> Result<Vec<String>, &'static str> {
+    let mut openings = Vec::new();
+
+    match &td["opening"] {
+        Value::Null => {}
+        Value::String(s) => openings.push(s.clone()),
+        _ => return Err("Opening must be a string"),
+    }
+
+    match &td["openings"] {
+        Value::Null => {}
+        Value::String(filename) => {
+            let contents =
+                fs::read_to_string(filename).map_err(|_| "Failed to read openings file")?;
+            openings.extend(
+                contents
+                    .lines()
+                    .map(|line| line.trim())
+                    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                    .map(|line| line.to_string()),
+            );
+        }
+        _ => return Err("Openings must be a file name"),
+    }
+
+    return Ok(openings);
+}
+
+fn read_format(v: &Value) -> Result<TournamentFormat, &'static str> {
+    return match v {
+        Value::String(s) if s == "round-robin" => Ok(TournamentFormat::RoundRobin),
+        Value::String(s) if s == "gauntlet" => Ok(TournamentFormat::Gauntlet),
+        _ => Err("Tournament must be \"round-robin\" or \"gauntlet\""),
+    };
+}
+
+enum Benchmark {
+    Match(Test),
+    Tournament(Tournament),
+}
+
+fn load_tests(v: Value) -> Result<Vec<Benchmark>, &'static str> {
     let test_defs: Vec<Value> = match v {
         Value::Array(v) => v,
         _ => return Err("Malformed JSON, expect root to be array"),
     };
 
-    let mut tests: Vec<Test> = Vec::new();
+    let mut tests: Vec<Benchmark> = Vec::new();
 
     for td in test_defs {
-        tests.push(Test {
-            label: match &td["label"] {
-                Value::String(s) => s.clone(),
-                _ => return Err("Label must be string"),
-            },
-            game: match &td["game"] {
-                Value::String(s) => read_game(&s).expect("Unknown game"),
-                _ => return Err("Game must be a string"),
-            },
-            p1_strat_conf: td["p1Strat"].clone(),
-            p2_strat_conf: td["p2Strat"].clone(),
-            iterations: match &td["iterations"] {
-                Value::Number(n) => {
-                    if n.is_u64() {
-                        n.as_u64().unwrap()
-                    } else {
-                        return Err("Iterations must be a positive integer");
-                    }
-                }
-                _ => return Err("Iterations must be a number"),
-            },
-        });
+        let label = match &td["label"] {
+            Value::String(s) => s.clone(),
+            _ => return Err("Label must be string"),
+        };
+        let game = read_game(&td["game"])?;
+
+        if td["tournament"].is_null() {
+            tests.push(Benchmark::Match(Test {
+                label: label,
+                game: game,
+                p1_strat_conf: td["p1Strat"].clone(),
+                p2_strat_conf: td["p2Strat"].clone(),
+                iterations: read_iterations(&td["iterations"])?,
+                threads: read_threads(&td["threads"])?,
+                seed: read_seed(&td["seed"])?,
+                swap_colors: read_swap_colors(&td["swapColors"])?,
+                sprt: read_sprt(&td["sprt"])?,
+                export: read_export(&td["export"])?,
+                openings: read_openings(&td)?,
+            }));
+        } else {
+            let strat_confs = match &td["strategies"] {
+                Value::Array(confs) if confs.len() >= 2 => confs.clone(),
+                _ => return Err("Strategies must be an array of at least two strategies"),
+            };
+
+            tests.push(Benchmark::Tournament(Tournament {
+                label: label,
+                game: game,
+                format: read_format(&td["tournament"])?,
+                strat_confs: strat_confs,
+                iterations: read_iterations(&td["iterations"])?,
+                threads: read_threads(&td["threads"])?,
+                seed: read_seed(&td["seed"])?,
+                swap_colors: read_swap_colors(&td["swapColors"])?,
+                export: read_export(&td["export"])?,
+                openings: read_openings(&td)?,
+            }));
+        }
     }
 
     return Ok(tests);
 }
 
+fn write_export(filename: &Option<String>, records: &[&str]) {
+    if let Some(filename) = filename {
+        fs::write(filename, records.join("\n")).expect("Failed to write export");
+    }
+}
+
+enum OutputFormat {
+    Text,
+    Json,
+    Csv,
+}
+
+fn read_args(args: &[String]) -> Result<(String, OutputFormat), &'static str> {
+    let mut filename = None;
+    let mut format = OutputFormat::Text;
+    let mut i = 1;
+
+    while i < args.len() {
+        if args[i] == "--format" {
+            i += 1;
+            format = match args.get(i).map(|s| s.as_str()) {
+                Some("text") => OutputFormat::Text,
+                Some("json") => OutputFormat::Json,
+                Some("csv") => OutputFormat::Csv,
+                _ => return Err("--format must be text, json or csv"),
+            };
+        } else if filename.is_none() {
+            filename = Some(args[i].clone());
+        } else {
+            return Err("Usage: benchmark <tests.json> [--format text|json|csv]");
+        }
+
+        i += 1;
+    }
+
+    return match filename {
+        Some(f) => Ok((f, format)),
+        None => Err("Usage: benchmark <tests.json> [--format text|json|csv]"),
+    };
+}
+
 fn main() {
     let args: Vec<String> = env::args().collect();
-    let filename = &args[1];
+    let (filename, format) = read_args(&args).expect("Invalid arguments");
 
     let contents = fs::read_to_string(filename).expect("Failed to read file");
 
     let v: Value = serde_json::from_str(&contents).expect("Failed to parse JSON");
     let tests = load_tests(v).expect("Failed to load tests");
 
+    // JSON results are printed together once every test has run, CSV rows as they come
+    let mut json_results = Vec::new();
+    if let OutputFormat::Csv = format {
+        println!("{}", CSV_HEADER);
+    }
+
     for test in tests {
-        let result = test.game.run(&test).expect("Failed to run test");
-        println!("{}", result);
+        let (text, json, csv) = match test {
+            Benchmark::Match(test) => {
+                let result = test.game.run(&test).expect("Failed to run test");
+                write_export(&test.export, &result.exports());
+                (result.to_string(), result.to_json(), result.csv_rows())
+            }
+            Benchmark::Tournament(tournament) => {
+                let result = tournament
+                    .game
+                    .run_tournament(&tournament)
+                    .expect("Failed to run tournament");
+                write_export(&tournament.export, &result.exports());
+                (result.to_string(), result.to_json(), result.csv_rows())
+            }
+        };
+
+        match format {
+            OutputFormat::Text => println!("{}", text),
+            OutputFormat::Json => json_results.push(json),
+            OutputFormat::Csv => print!("{}", csv),
+        }
+    }
+
+    if let OutputFormat::Json = format {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&Value::Array(json_results))
+                .expect("Failed to write JSON")
+        );
     }
 }
@@ -5,7 +5,14 @@ use board_game_ai::playground;
 use board_game_ai::games;
 
 fn games() -> Vec<Box<dyn playground::PlaygroundGame>> {
-    return vec![Box::new(games::TicTacToe {}), Box::new(games::Chess {})];
+    return vec![
+        Box::new(games::MnkGame::tic_tac_toe()),
+        Box::new(games::MnkGame::gomoku()),
+        Box::new(games::Chess::new()),
+        Box::new(games::ConnectFour {}),
+        Box::new(games::Othello {}),
+        Box::new(games::Go::new()),
+    ];
 }
 
 fn select_game() -> Box<dyn playground::PlaygroundGame> {
@@ -0,0 +1,324 @@
+use board_game_ai::core::{Game, Player, SearchInfo, Strategy};
+use board_game_ai::games::{action_from_string, Chess, ChessAction, ChessState};
+use board_game_ai::playground::PlaygroundUtils;
+
+use serde_json::{json, Value};
+
+use std::env;
+use std::io;
+use std::io::BufRead;
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+// Strategies that can't play through UCI: one waits for a human, the other is itself a UCI
+// engine
+const EXCLUDED_STRATEGIES: [&str; 2] = ["Human", "UCI"];
+
+// Depth limit for searches that only end on "stop", high enough to never be reached
+const MAX_DEPTH: u64 = 64;
+
+// Time kept back for communication with the GUI
+const MOVE_OVERHEAD_MS: u64 = 20;
+
+// Moves the remaining time is spread over when the GUI doesn't say
+const DEFAULT_MOVES_TO_GO: u64 = 30;
+
+enum Event {
+    Command(String),
+    BestMove(ChessAction, Option<SearchInfo<ChessAction>>),
+}
+
+struct Search {
+    // Started with "go infinite", the best move is held back until "stop"
+    infinite: bool,
+    stopped: bool,
+    // Shared with the strategy, makes it return early
+    stop: Arc<AtomicBool>,
+    result: Option<String>,
+}
+
+struct Engine {
+    game: Chess,
+    strategy: String,
+    // Applied to the strategy before the limits of each "go"
+    config: Value,
+    state: ChessState,
+    search: Option<Search>,
+}
+
+fn strategy_names(game: &Chess) -> Vec<String> {
+    return game
+        .strategies()
+        .iter()
+        .map(|s| s.name())
+        .filter(|name| !EXCLUDED_STRATEGIES.contains(&name.as_str()))
+        .collect();
+}
+
+fn select_strategy(game: &Chess, name: &str) -> Option<Box<dyn Strategy<Chess>>> {
+    return game.strategies().into_iter().find(|s| s.name() == name);
+}
+
+fn action_to_string(action: &ChessAction) -> String {
+    return action.chess_move.to_string();
+}
+
+// "info" line describing a finished search
+fn info_line(info: &SearchInfo<ChessAction>) -> String {
+    let mut line = format!("info depth {} nodes {}", info.depth, info.nodes);
+
+    if !info.pv.is_empty() {
+        let pv: Vec<String> = info.pv.iter().map(action_to_string).collect();
+        line.push_str(&format!(" pv {}", pv.join(" ")));
+    }
+
+    return line;
+}
+
+// Value of the token following name in a "go" command
+fn go_param(tokens: &[&str], name: &str) -> Option<u64> {
+    let idx = tokens.iter().position(|&t| t == name)?;
+    return tokens.get(idx + 1).and_then(|t| t.parse().ok());
+}
+
+// Strategy settings for the limits of a "go" command, empty when it has none
+fn go_limits(tokens: &[&str], player: Player) -> Value {
+    let (time, inc) = match player {
+        Player::Player1 => (go_param(tokens, "wtime"), go_param(tokens, "winc")),
+        Player::Player2 => (go_param(tokens, "btime"), go_param(tokens, "binc")),
+    };
+
+    let time_limit = match go_param(tokens, "movetime") {
+        Some(movetime) => Some(movetime.saturating_sub(MOVE_OVERHEAD_MS).max(1)),
+        None => time.map(|time| {
+            let moves_to_go = go_param(tokens, "movestogo").unwrap_or(DEFAULT_MOVES_TO_GO);
+            let budget = time / moves_to_go.max(1) + inc.unwrap_or(0) / 2;
+            return budget.min(time.saturating_sub(MOVE_OVERHEAD_MS)).max(1);
+        }),
+    };
+
+    let mut limits = json!({});
+
+    // MinMax searches the children of the root to depth, so the root adds a ply. The depth
+    // also caps iterative deepening when there is a time limit.
+    if let Some(depth) = go_param(tokens, "depth") {
+        let depth = depth.saturating_sub(1).min(MAX_DEPTH);
+        limits["depth"] = json!(depth);
+        limits["maxDepth"] = json!(depth);
+    } else if tokens.contains(&"infinite") {
+        limits["depth"] = json!(MAX_DEPTH);
+    }
+
+    if let Some(time_limit) = time_limit {
+        limits["timeLimitMs"] = json!(time_limit);
+    }
+
+    return limits;
+}
+
+impl Engine {
+    fn new(strategy: String, config: Value) -> Engine {
+        let game = Chess::new();
+        let state = game.init();
+
+        return Engine {
+            game: game,
+            strategy: strategy,
+            config: config,
+            state: state,
+            search: None,
+        };
+    }
+
+    fn uci(&self) {
+        println!("id name board_game_ai {}", self.strategy);
+        println!("id author board_game_ai");
+
+        let mut option = format!("option name Strategy type combo default {}", self.strategy);
+        for name in strategy_names(&self.game) {
+            option.push_str(&format!(" var {}", name));
+        }
+        println!("{}", option);
+        println!("option name Config type string default {}", self.config);
+        println!("uciok");
+    }
+
+    fn set_option(&mut self, command: &str) {
+        let rest = command.trim_start_matches("setoption").trim();
+        let (name, value) = match rest.find(" value ") {
+            Some(idx) => (&rest[..idx], rest[idx + " value ".len()..].trim()),
+            None => (rest, ""),
+        };
+
+        match name.trim_start_matches("name").trim() {
+            "Strategy" => {
+                if strategy_names(&self.game).iter().any(|n| n == value) {
+                    self.strategy = value.to_string();
+                } else {
+                    println!("info string unknown strategy {}", value);
+                }
+            }
+            "Config" => match serde_json::from_str::<Value>(value) {
+                Ok(config) if config.is_object() => self.config = config,
+                _ => println!("info string Config must be a JSON object"),
+            },
+            other => println!("info string unknown option {}", other),
+        }
+    }
+
+    fn position(&mut self, tokens: &[&str]) {
+        let moves_idx = tokens.iter().position(|&t| t == "moves");
+        let setup = &tokens[1..moves_idx.unwrap_or(tokens.len())];
+
+        let state = match setup.first() {
+            Some(&"startpos") => Some(self.game.init()),
+            Some(&"fen") => self.game.parse_state(&setup[1..].join(" ")),
+            _ => None,
+        };
+        let mut state = match state {
+            Some(state) => state,
+            None => {
+                println!("info string invalid position");
+                return;
+            }
+        };
+
+        if let Some(idx) = moves_idx {
+            for token in &tokens[idx + 1..] {
+                let action = match action_from_string(token) {
+                    Some(action) if self.game.actions(&state).contains(&action) => action,
+                    _ => {
+                        println!("info string illegal move {}", token);
+                        return;
+                    }
+                };
+                state = self.game.play(&action, &state);
+            }
+        }
+
+        self.state = state;
+    }
+
+    fn go(&mut self, tokens: &[&str], events: &mpsc::Sender<Event>) {
+        if self.search.is_some() {
+            println!("info string already searching");
+            return;
+        }
+
+        let limits = go_limits(tokens, self.game.player(&self.state));
+        let strategy = self.strategy.clone();
+        let config = self.config.clone();
+        let state = self.state.clone();
+        let events = events.clone();
+        let stop = Arc::new(AtomicBool::new(false));
+
+        self.search = Some(Search {
+            infinite: tokens.contains(&"infinite"),
+            stopped: false,
+            stop: stop.clone(),
+            result: None,
+        });
+
+        // The search runs on its own thread so the GUI keeps being answered meanwhile,
+        // "stop" reaches it through the stop flag
+        thread::spawn(move || {
+            let game = Chess::new();
+            let strat = select_strategy(&game, &strategy).expect("No matching strategy");
+            strat.configure(&config);
+            strat.configure(&limits);
+            strat.set_stop_flag(stop);
+
+            let action = strat.select_action(&game, &state);
+            let _ = events.send(Event::BestMove(action, strat.search_info()));
+        });
+    }
+
+    fn stop(&mut self) {
+        let finished = match &mut self.search {
+            Some(search) => {
+                search.stopped = true;
+                search.stop.store(true, Ordering::Relaxed);
+                search.result.take()
+            }
+            None => None,
+        };
+
+        if let Some(result) = finished {
+            println!("{}", result);
+            self.search = None;
+        }
+    }
+
+    fn best_move(&mut self, action: ChessAction, info: Option<SearchInfo<ChessAction>>) {
+        if let Some(info) = &info {
+            println!("{}", info_line(info));
+        }
+
+        let result = format!("bestmove {}", action_to_string(&action));
+
+        match &mut self.search {
+            Some(search) if search.infinite && !search.stopped => search.result = Some(result),
+            _ => {
+                println!("{}", result);
+                self.search = None;
+            }
+        }
+    }
+}
+
+// Usage: uci_engine [strategy] [strategy config as JSON]
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let strategy = args
+        .get(1)
+        .cloned()
+        .unwrap_or_else(|| "MinMaxAB".to_string());
+    let config = match args.get(2) {
+        Some(c) => serde_json::from_str(c).expect("Config must be JSON"),
+        None => json!({}),
+    };
+
+    // Engines greet with a line before any command, some clients wait for it
+    println!("board_game_ai UCI engine playing {}", strategy);
+
+    let mut engine = Engine::new(strategy, config);
+    let (events, received) = mpsc::channel();
+
+    let commands = events.clone();
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines() {
+            let line = line.expect("Failed to read line");
+            if commands.send(Event::Command(line)).is_err() {
+                return;
+            }
+        }
+        let _ = commands.send(Event::Command("quit".to_string()));
+    });
+
+    for event in received {
+        let command = match event {
+            Event::BestMove(action, info) => {
+                engine.best_move(action, info);
+                continue;
+            }
+            Event::Command(command) => command,
+        };
+
+        let tokens: Vec<&str> = command.split_whitespace().collect();
+
+        match tokens.first() {
+            Some(&"uci") => engine.uci(),
+            Some(&"isready") => println!("readyok"),
+            Some(&"setoption") => engine.set_option(&command),
+            Some(&"ucinewgame") => engine.state = engine.game.init(),
+            Some(&"position") => engine.position(&tokens),
+            Some(&"go") => engine.go(&tokens, &events),
+            Some(&"stop") => engine.stop(),
+            Some(&"quit") => process::exit(0),
+            _ => {}
+        }
+    }
+}
@@ -1,4 +1,6 @@
 use std::fmt;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
 use std::time;
 
 use serde_json::Value;
@@ -36,8 +38,9 @@ impl fmt::Display for GameStatus {
 }
 
 pub trait Game {
-    type State: Clone;
-    type Action: Clone + Eq;
+    // Thread safe so that strategies can search a position from several threads
+    type State: Clone + Send + Sync;
+    type Action: Clone + Eq + Send + Sync;
 
     // Basic game functions
     fn name(&self) -> String;
@@ -46,30 +49,163 @@ pub trait Game {
     fn actions(&self, state: &Self::State) -> Vec<Self::Action>;
     fn play(&self, action: &Self::Action, state: &Self::State) -> Self::State;
     fn status(&self, state: &Self::State) -> GameStatus;
+
+    // Optional search support
+
+    // Hash identifying a position for transposition tables, None if the game doesn't provide one
+    fn state_hash(&self, _state: &Self::State) -> Option<u64> {
+        return None;
+    }
+
+    // Whether action changes the position too sharply for a static evaluation to be trusted
+    // (captures, promotions, escaping check, ...). Quiescence search keeps playing these.
+    fn is_tactical(&self, _state: &Self::State, _action: &Self::Action) -> bool {
+        return false;
+    }
 }
 
 pub trait Strategy<G: Game> {
     fn name(&self) -> String;
     fn select_action(&self, game: &G, state: &G::State) -> G::Action;
     fn configure(&self, _conf: &Value) {}
+
+    // Reseeds the strategy's random number generator, if it has one, so that its choices
+    // can be reproduced
+    fn seed(&self, _seed: u64) {}
+
+    // Flag that makes select_action return the best action found so far once it is set.
+    // Strategies that can't be interrupted ignore it.
+    fn set_stop_flag(&self, _stop: Arc<AtomicBool>) {}
+
+    // Statistics about the most recent select_action call, if the strategy keeps any
+    fn search_info(&self) -> Option<SearchInfo<G::Action>> {
+        return None;
+    }
+}
+
+// What a strategy found out while choosing a single action. Fields that don't apply to a
+// strategy are left at zero.
+#[derive(Clone)]
+pub struct SearchInfo<A> {
+    pub nodes: u64,
+    // Plies searched, for MCTS the length of the most visited line
+    pub depth: u32,
+    pub playouts: u64,
+    pub tt_probes: u64,
+    pub tt_hits: u64,
+    // Expected continuation, starting with the chosen action
+    pub pv: Vec<A>,
+}
+
+impl<A> SearchInfo<A> {
+    pub fn new() -> SearchInfo<A> {
+        return SearchInfo {
+            nodes: 0,
+            depth: 0,
+            playouts: 0,
+            tt_probes: 0,
+            tt_hits: 0,
+            pv: Vec::new(),
+        };
+    }
+}
+
+// Sum of the SearchInfo of many moves
+#[derive(Clone, Default)]
+pub struct SearchTotals {
+    pub searches: u64,
+    pub nodes: u64,
+    pub depth: u64,
+    pub playouts: u64,
+    pub tt_probes: u64,
+    pub tt_hits: u64,
+    pub time: time::Duration,
+}
+
+impl SearchTotals {
+    pub fn add<A>(&mut self, info: &SearchInfo<A>, time: time::Duration) {
+        self.searches += 1;
+        self.nodes += info.nodes;
+        self.depth += info.depth as u64;
+        self.playouts += info.playouts;
+        self.tt_probes += info.tt_probes;
+        self.tt_hits += info.tt_hits;
+        self.time += time;
+    }
+
+    pub fn merge(&mut self, other: &SearchTotals) {
+        self.searches += other.searches;
+        self.nodes += other.nodes;
+        self.depth += other.depth;
+        self.playouts += other.playouts;
+        self.tt_probes += other.tt_probes;
+        self.tt_hits += other.tt_hits;
+        self.time += other.time;
+    }
+
+    pub fn nodes_per_sec(&self) -> f64 {
+        if self.time.as_secs_f64() == 0.0 {
+            return 0.0;
+        }
+
+        return self.nodes as f64 / self.time.as_secs_f64();
+    }
+
+    pub fn avg_depth(&self) -> f64 {
+        if self.searches == 0 {
+            return 0.0;
+        }
+
+        return self.depth as f64 / self.searches as f64;
+    }
+
+    pub fn tt_hit_rate(&self) -> f64 {
+        if self.tt_probes == 0 {
+            return 0.0;
+        }
+
+        return self.tt_hits as f64 / self.tt_probes as f64;
+    }
+}
+
+// One action of a match
+#[derive(Clone)]
+pub struct MoveRecord {
+    pub player: Player,
+    // As written by PlaygroundUtils::serialize_action
+    pub action: String,
+    pub time: time::Duration,
 }
 
 pub struct MatchResult {
     pub status: GameStatus,
+    // Why the game ended, as given by PlaygroundUtils::termination
+    pub termination: String,
+    // Position the game started from, as given to PlaygroundUtils::parse_state. None for
+    // the game's initial position.
+    pub opening: Option<String>,
     pub num_moves: u32,
+    pub moves: Vec<MoveRecord>,
     pub player1_time: time::Duration,
     pub player2_time: time::Duration,
+    pub player1_search: SearchTotals,
+    pub player2_search: SearchTotals,
 }
 
 impl fmt::Display for MatchResult {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "Match result:\t{}\n# Moves:\t{}\nPlayer 1 Time:\t{}\nPlayer 2 Time:\t{}",
+            "Match result:\t{}\nTermination:\t{}\n# Moves:\t{}\nPlayer 1 Time:\t{}\nPlayer 2 Time:\t{}\nPlayer 1 Nodes/sec:\t{:.0}\nPlayer 2 Nodes/sec:\t{:.0}\nPlayer 1 Avg Depth:\t{:.2}\nPlayer 2 Avg Depth:\t{:.2}",
             self.status,
+            self.termination,
             self.num_moves,
             self.player1_time.as_millis(),
-            self.player2_time.as_millis()
+            self.player2_time.as_millis(),
+            self.player1_search.nodes_per_sec(),
+            self.player2_search.nodes_per_sec(),
+            self.player1_search.avg_depth(),
+            self.player2_search.avg_depth()
         )
     }
 }
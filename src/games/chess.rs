@@ -6,16 +6,81 @@ use crate::playground;
 use crate::strategy;
 
 use std::io;
+use std::str::FromStr;
+use std::time::Duration;
 
-pub struct Chess {}
+// Draws by the 50-move rule once this many halfmoves pass without a capture or pawn move
+const FIFTY_MOVE_HALFMOVES: u32 = 100;
+
+pub struct Chess {
+    // Games still going after this many full moves are adjudicated as draws
+    pub max_moves: Option<u32>,
+}
+
+impl Chess {
+    pub fn new() -> Chess {
+        return Chess { max_moves: None };
+    }
+
+    pub fn with_max_moves(mut self, max_moves: u32) -> Chess {
+        self.max_moves = Some(max_moves);
+        return self;
+    }
+}
 
 #[derive(Clone)]
 pub struct ChessState {
     pub board: Board,
-    pub num_moves: u8,
+    // Full moves played, counted after each black move
+    pub num_moves: u32,
+    // Halfmoves since the last capture or pawn move
+    pub halfmove_clock: u32,
+    // Hashes of the positions since the last capture or pawn move, the current one excluded.
+    // Earlier positions can't come back.
+    pub history: Vec<u64>,
+}
+
+impl ChessState {
+    pub fn new(board: Board) -> ChessState {
+        return ChessState {
+            board: board,
+            num_moves: 0,
+            halfmove_clock: 0,
+            history: Vec::new(),
+        };
+    }
+
+    pub fn repetitions(&self) -> usize {
+        let hash = self.board.get_hash();
+        return self.history.iter().filter(|&&h| h == hash).count() + 1;
+    }
+}
+
+// Neither side can checkmate by any sequence of legal moves: bare kings, or kings with
+// bishops all standing on squares of the same color and at most one knight when there
+// are no bishops
+fn insufficient_material(board: &Board) -> bool {
+    let heavy =
+        *board.pieces(Piece::Pawn) | *board.pieces(Piece::Rook) | *board.pieces(Piece::Queen);
+    if heavy.popcnt() > 0 {
+        return false;
+    }
+
+    let knights = board.pieces(Piece::Knight).popcnt();
+    let bishops = *board.pieces(Piece::Bishop);
+
+    if knights == 0 {
+        let light = bishops
+            .into_iter()
+            .filter(|sq| (sq.get_rank().to_index() + sq.get_file().to_index()) % 2 == 1)
+            .count();
+        return light == 0 || light == bishops.popcnt() as usize;
+    }
+
+    return knights == 1 && bishops.popcnt() == 0;
 }
 
-#[derive(PartialEq, Eq, Clone)]
+#[derive(PartialEq, Eq, Clone, Hash)]
 pub struct ChessAction {
     pub chess_move: ChessMove,
 }
@@ -29,12 +94,7 @@ impl core::Game for Chess {
     }
 
     fn init(&self) -> Self::State {
-        let state = Self::State {
-            board: Board::default(),
-            num_moves: 0,
-        };
-
-        return state;
+        return ChessState::new(Board::default());
     }
 
     fn player(&self, state: &Self::State) -> core::Player {
@@ -61,17 +121,34 @@ impl core::Game for Chess {
 
     fn play(&self, action: &Self::Action, state: &Self::State) -> Self::State {
         let new_board = state.board.make_move_new(action.chess_move);
+        let irreversible = state.board.piece_on(action.chess_move.get_source())
+            == Some(Piece::Pawn)
+            || captured_piece(&state.board, action.chess_move).is_some();
+
+        let (halfmove_clock, history) = if irreversible {
+            (0, Vec::new())
+        } else {
+            let mut history = state.history.clone();
+            history.push(state.board.get_hash());
+            (state.halfmove_clock + 1, history)
+        };
 
         return ChessState {
             board: new_board,
-            num_moves: state.num_moves + (self.player(state) == core::Player::Player2) as u8,
+            num_moves: state.num_moves + (self.player(state) == core::Player::Player2) as u32,
+            halfmove_clock: halfmove_clock,
+            history: history,
         };
     }
 
     fn status(&self, state: &Self::State) -> core::GameStatus {
         return match state.board.status() {
             BoardStatus::Ongoing => {
-                if state.num_moves > 50 {
+                if state.halfmove_clock >= FIFTY_MOVE_HALFMOVES
+                    || state.repetitions() >= 3
+                    || insufficient_material(&state.board)
+                    || self.max_moves.is_some_and(|max| state.num_moves >= max)
+                {
                     core::GameStatus::Draw
                 } else {
                     core::GameStatus::InProgress
@@ -87,6 +164,31 @@ impl core::Game for Chess {
             }
         };
     }
+
+    fn state_hash(&self, state: &Self::State) -> Option<u64> {
+        return Some(state.board.get_hash());
+    }
+
+    fn is_tactical(&self, state: &Self::State, action: &Self::Action) -> bool {
+        // Every move out of check counts, so the search never stands pat while in check
+        return state.board.checkers().popcnt() > 0
+            || captured_piece(&state.board, action.chess_move).is_some()
+            || action.chess_move.get_promotion().is_some();
+    }
+}
+
+fn captured_piece(board: &Board, chess_move: ChessMove) -> Option<Piece> {
+    let attacker = board.piece_on(chess_move.get_source());
+
+    return match board.piece_on(chess_move.get_dest()) {
+        // A pawn changing files onto an empty square captures en passant
+        None if attacker == Some(Piece::Pawn)
+            && chess_move.get_source().get_file() != chess_move.get_dest().get_file() =>
+        {
+            Some(Piece::Pawn)
+        }
+        victim => victim,
+    };
 }
 
 pub fn action_from_string(string: &str) -> Option<ChessAction> {
@@ -180,13 +282,14 @@ pub fn action_from_string(string: &str) -> Option<ChessAction> {
 
     let mut promo = None;
 
+    // Long algebraic notation, as UCI uses it, writes the promotion piece in lowercase
     if bytes.len() == 5 {
         promo = match bytes[4] as char {
-            'K' => Some(Piece::King),
-            'Q' => Some(Piece::Queen),
-            'R' => Some(Piece::Rook),
-            'N' => Some(Piece::Knight),
-            _ => None,
+            'q' => Some(Piece::Queen),
+            'r' => Some(Piece::Rook),
+            'b' => Some(Piece::Bishop),
+            'n' => Some(Piece::Knight),
+            _ => return None,
         }
     }
 
@@ -209,7 +312,7 @@ impl core::ActionParser for ChessParser {
                 .read_line(&mut move_str)
                 .expect("Failed to read line");
 
-            let action = action_from_string(&move_str);
+            let action = action_from_string(move_str.trim());
 
             if action == None {
                 continue;
@@ -220,49 +323,263 @@ impl core::ActionParser for ChessParser {
     }
 }
 
-pub struct ChessHeuristic {}
+fn piece_letter(piece: Piece) -> char {
+    return match piece {
+        Piece::Pawn => 'P',
+        Piece::Knight => 'N',
+        Piece::Bishop => 'B',
+        Piece::Rook => 'R',
+        Piece::Queen => 'Q',
+        Piece::King => 'K',
+    };
+}
 
-impl strategy::Heuristic<Chess> for ChessHeuristic {
-    fn evaluate(&self, game: &Chess, state: &ChessState, player: core::Player) -> f64 {
-        // Score is the number of ways player has to win, or infinity if the player has won
-        match (game.status(state), player) {
-            (core::GameStatus::Player1Win, core::Player::Player1) => return f64::INFINITY,
-            (core::GameStatus::Player2Win, core::Player::Player2) => return f64::INFINITY,
-            (core::GameStatus::Player2Win, core::Player::Player1) => return f64::NEG_INFINITY,
-            (core::GameStatus::Player1Win, core::Player::Player2) => return f64::NEG_INFINITY,
-            (core::GameStatus::Draw, _) => return 1.0,
-            _ => (),
+// Standard algebraic notation of chess_move, played from board
+pub fn san(board: &Board, chess_move: ChessMove) -> String {
+    let src = chess_move.get_source();
+    let dst = chess_move.get_dest();
+    let piece = board.piece_on(src).expect("No piece to move");
+    let file_distance = src.get_file().to_index() as i32 - dst.get_file().to_index() as i32;
+    let mut san = String::new();
+
+    if piece == Piece::King && file_distance.abs() == 2 {
+        san.push_str(if dst.get_file() == File::G {
+            "O-O"
+        } else {
+            "O-O-O"
+        });
+    } else {
+        let capture = captured_piece(board, chess_move).is_some();
+        let src_name = src.to_string();
+
+        if piece == Piece::Pawn {
+            if capture {
+                san.push_str(&src_name[..1]);
+            }
+        } else {
+            san.push(piece_letter(piece));
+
+            // Name as much of the source square as needed to tell apart pieces of the same
+            // kind that could also move to dst
+            let rivals: Vec<Square> = MoveGen::new_legal(board)
+                .filter(|m| {
+                    m.get_dest() == dst
+                        && m.get_source() != src
+                        && board.piece_on(m.get_source()) == Some(piece)
+                })
+                .map(|m| m.get_source())
+                .collect();
+
+            if !rivals.is_empty() {
+                if rivals.iter().all(|r| r.get_file() != src.get_file()) {
+                    san.push_str(&src_name[..1]);
+                } else if rivals.iter().all(|r| r.get_rank() != src.get_rank()) {
+                    san.push_str(&src_name[1..]);
+                } else {
+                    san.push_str(&src_name);
+                }
+            }
         }
 
-        let mut white_material = 0;
-        let mut black_material = 0;
+        if capture {
+            san.push('x');
+        }
 
-        // Count difference between our material score and opponent's material score
-        for i in 0..64 {
-            let square =
-                Square::make_square(Rank::from_index(i % 8), File::from_index((i - i % 8) / 8));
-            let color = state.board.color_on(square);
+        san.push_str(&dst.to_string());
 
-            if color == None {
-                continue;
-            }
+        if let Some(promotion) = chess_move.get_promotion() {
+            san.push('=');
+            san.push(piece_letter(promotion));
+        }
+    }
 
-            let score_var = if color == Some(Color::White) {
-                &mut white_material
-            } else {
-                &mut black_material
-            };
+    let after = board.make_move_new(chess_move);
+    if after.status() == BoardStatus::Checkmate {
+        san.push('#');
+    } else if after.checkers().popcnt() > 0 {
+        san.push('+');
+    }
 
-            match state.board.piece_on(square) {
-                Some(Piece::Pawn) => *score_var += 1,
-                Some(Piece::Bishop) => *score_var += 3,
-                Some(Piece::Knight) => *score_var += 3,
-                Some(Piece::Rook) => *score_var += 5,
-                Some(Piece::Queen) => *score_var += 9,
-                _ => continue,
-            }
+    return san;
+}
+
+fn pgn_tag(name: &str, value: &str) -> String {
+    return format!(
+        "[{} \"{}\"]\n",
+        name,
+        value.replace('\\', "\\\\").replace('"', "\\\"")
+    );
+}
+
+// Elapsed move time comment, understood by most chess GUIs
+fn pgn_emt(time: Duration) -> String {
+    let millis = time.as_millis();
+    return format!(
+        "{{[%emt {}:{:02}:{:02}.{:03}]}}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        millis % 1000
+    );
+}
+
+// A finished match in PGN, with moves as written by serialize_action
+pub fn pgn(
+    result: &core::MatchResult,
+    event: &str,
+    round: u64,
+    white: &str,
+    black: &str,
+) -> String {
+    let result_str = match result.status {
+        core::GameStatus::Player1Win => "1-0",
+        core::GameStatus::Player2Win => "0-1",
+        core::GameStatus::Draw => "1/2-1/2",
+        core::GameStatus::InProgress => "*",
+    };
+    let termination = match result.termination.as_str() {
+        "move limit" => "adjudication",
+        _ => "normal",
+    };
+
+    let mut pgn = String::new();
+    pgn.push_str(&pgn_tag("Event", event));
+    pgn.push_str(&pgn_tag("Site", "?"));
+    pgn.push_str(&pgn_tag("Date", "????.??.??"));
+    pgn.push_str(&pgn_tag("Round", &round.to_string()));
+    pgn.push_str(&pgn_tag("White", white));
+    pgn.push_str(&pgn_tag("Black", black));
+    pgn.push_str(&pgn_tag("Result", result_str));
+    pgn.push_str(&pgn_tag("Termination", termination));
+    pgn.push_str(&pgn_tag("PlyCount", &result.moves.len().to_string()));
+
+    // Games from an opening continue its move numbers, starting with its side to move
+    let mut first_ply = 0;
+    if let Some(opening) = &result.opening {
+        let fields: Vec<&str> = opening.split_whitespace().collect();
+        let counter = |i: usize, default: u32| {
+            return fields
+                .get(i)
+                .and_then(|f| f.parse::<u32>().ok())
+                .unwrap_or(default);
+        };
+        let fullmove = counter(5, 1).max(1);
+        first_ply = (fullmove as usize - 1) * 2 + (fields.get(1) == Some(&"b")) as usize;
+
+        let fen = format!(
+            "{} {} {} {} {} {}",
+            fields[0],
+            fields[1],
+            fields[2],
+            fields[3],
+            counter(4, 0),
+            fullmove
+        );
+        pgn.push_str(&pgn_tag("SetUp", "1"));
+        pgn.push_str(&pgn_tag("FEN", &fen));
+    }
+    pgn.push('\n');
+
+    // Every move is followed by a comment, so black's moves need their own move number
+    let mut tokens = Vec::new();
+    for (i, record) in result.moves.iter().enumerate() {
+        let ply = first_ply + i;
+        if ply % 2 == 0 {
+            tokens.push(format!("{}.", ply / 2 + 1));
+        } else {
+            tokens.push(format!("{}...", ply / 2 + 1));
+        }
+        tokens.push(record.action.clone());
+        tokens.push(pgn_emt(record.time));
+    }
+    tokens.push(format!("{{{}}}", result.termination));
+    tokens.push(result_str.to_string());
+
+    // Export format keeps lines under 80 characters
+    let mut line = String::new();
+    for token in tokens {
+        if !line.is_empty() && line.len() + 1 + token.len() > 79 {
+            pgn.push_str(&line);
+            pgn.push('\n');
+            line.clear();
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(&token);
+    }
+    pgn.push_str(&line);
+    pgn.push('\n');
+
+    return pgn;
+}
+
+// Score for a finished game from player's point of view, None if the game is still going
+fn terminal_score(game: &Chess, state: &ChessState, player: core::Player) -> Option<f64> {
+    return match (game.status(state), player) {
+        (core::GameStatus::Player1Win, core::Player::Player1) => Some(f64::INFINITY),
+        (core::GameStatus::Player2Win, core::Player::Player2) => Some(f64::INFINITY),
+        (core::GameStatus::Player2Win, core::Player::Player1) => Some(f64::NEG_INFINITY),
+        (core::GameStatus::Player1Win, core::Player::Player2) => Some(f64::NEG_INFINITY),
+        (core::GameStatus::Draw, _) => Some(1.0),
+        _ => None,
+    };
+}
+
+fn piece_value(piece: Piece) -> u32 {
+    return match piece {
+        Piece::Pawn => 1,
+        Piece::Bishop => 3,
+        Piece::Knight => 3,
+        Piece::Rook => 5,
+        Piece::Queen => 9,
+        Piece::King => 0,
+    };
+}
+
+// Returns the (white, black) material count
+fn material(board: &Board) -> (u32, u32) {
+    let mut white_material = 0;
+    let mut black_material = 0;
+
+    for i in 0..64 {
+        let square =
+            Square::make_square(Rank::from_index(i % 8), File::from_index((i - i % 8) / 8));
+        let color = board.color_on(square);
+
+        if color == None {
+            continue;
+        }
+
+        let score_var = if color == Some(Color::White) {
+            &mut white_material
+        } else {
+            &mut black_material
+        };
+
+        if let Some(piece) = board.piece_on(square) {
+            *score_var += piece_value(piece);
+        }
+    }
+
+    return (white_material, black_material);
+}
+
+pub struct ChessHeuristic {}
+
+impl strategy::Heuristic<Chess> for ChessHeuristic {
+    fn name(&self) -> String {
+        return "checks".to_string();
+    }
+
+    fn evaluate(&self, game: &Chess, state: &ChessState, player: core::Player) -> f64 {
+        if let Some(score) = terminal_score(game, state, player) {
+            return score;
         }
 
+        // Count difference between our material score and opponent's material score
+        let (white_material, black_material) = material(&state.board);
+
         let checkers = state.board.checkers().popcnt();
         let black_checkers = if game.player(state) == core::Player::Player1 {
             checkers
@@ -286,31 +603,78 @@ impl strategy::Heuristic<Chess> for ChessHeuristic {
     }
 }
 
+// Plain material balance, without ChessHeuristic's bonus for giving check
+pub struct ChessMaterialHeuristic {}
+
+impl strategy::Heuristic<Chess> for ChessMaterialHeuristic {
+    fn name(&self) -> String {
+        return "material".to_string();
+    }
+
+    fn evaluate(&self, game: &Chess, state: &ChessState, player: core::Player) -> f64 {
+        if let Some(score) = terminal_score(game, state, player) {
+            return score;
+        }
+
+        let (white_material, black_material) = material(&state.board);
+        let white_score = (white_material as i32 - black_material as i32) as f64;
+
+        if player == core::Player::Player1 {
+            return white_score;
+        } else {
+            return -white_score;
+        }
+    }
+}
+
+// Most valuable victim / least valuable attacker: captures come first, winning the biggest
+// piece with the smallest one, followed by promotions and then every quiet move
+pub struct ChessOrdering {}
+
+impl strategy::ActionOrdering<Chess> for ChessOrdering {
+    fn priority(&self, _game: &Chess, state: &ChessState, action: &ChessAction) -> i32 {
+        let chess_move = action.chess_move;
+        let attacker = state.board.piece_on(chess_move.get_source());
+        let victim = captured_piece(&state.board, chess_move);
+
+        let mut priority = match (victim, attacker) {
+            (Some(v), Some(a)) => 10 * piece_value(v) as i32 - piece_value(a) as i32 + 10,
+            _ => 0,
+        };
+
+        if let Some(promotion) = chess_move.get_promotion() {
+            priority += piece_value(promotion) as i32;
+        }
+
+        return priority;
+    }
+}
+
+fn heuristics() -> Vec<Box<dyn strategy::Heuristic<Chess>>> {
+    return vec![
+        Box::new(ChessHeuristic {}),
+        Box::new(ChessMaterialHeuristic {}),
+    ];
+}
+
 impl playground::PlaygroundUtils for Chess {
     fn strategies(&self) -> Vec<Box<dyn core::Strategy<Self>>> {
-        let mut strats: Vec<Box<dyn core::Strategy<Self>>> = vec![
+        return vec![
             Box::new(strategy::HumanStrategy {
                 parser: ChessParser {},
             }),
-            Box::new(strategy::RandomStrategy {}),
-            Box::new(strategy::MinMaxStrategy {
-                heuristic: Box::new(ChessHeuristic {}),
-                search_depth: 3,
-                alpha_beta: false,
-            }),
-            Box::new(strategy::MinMaxStrategy {
-                heuristic: Box::new(ChessHeuristic {}),
-                search_depth: 3,
-                alpha_beta: true,
-            }),
-            Box::new(strategy::MCTSStrategy {}),
+            Box::new(strategy::RandomStrategy::new()),
+            Box::new(
+                strategy::MinMaxStrategy::new(heuristics(), 3, false)
+                    .with_ordering(Box::new(ChessOrdering {})),
+            ),
+            Box::new(
+                strategy::MinMaxStrategy::new(heuristics(), 3, true)
+                    .with_ordering(Box::new(ChessOrdering {})),
+            ),
+            Box::new(strategy::MCTSStrategy::new()),
+            Box::new(strategy::UCIStrategy::new()),
         ];
-
-        if let Some(e) = strategy::UCIStrategy::new() {
-            strats.push(Box::new(e));
-        }
-
-        return strats;
     }
 
     fn serialize_state(&self, state: &ChessState) -> String {
@@ -414,4 +778,60 @@ impl playground::PlaygroundUtils for Chess {
             piece_string(&state.board, Square::H8),
         );
     }
+
+    // FEN, or EPD whose operations are ignored
+    fn parse_state(&self, s: &str) -> Option<ChessState> {
+        let fields: Vec<&str> = s.split_whitespace().collect();
+        if fields.len() < 4 {
+            return None;
+        }
+
+        let halfmove_clock = fields.get(4).and_then(|f| f.parse::<u32>().ok());
+        let fen = format!(
+            "{} {} {} {} {} 1",
+            fields[0],
+            fields[1],
+            fields[2],
+            fields[3],
+            halfmove_clock.unwrap_or(0)
+        );
+
+        let mut state = ChessState::new(Board::from_str(&fen).ok()?);
+        state.halfmove_clock = halfmove_clock.unwrap_or(0);
+
+        return Some(state);
+    }
+
+    fn serialize_action(&self, state: &ChessState, action: &ChessAction) -> String {
+        return san(&state.board, action.chess_move);
+    }
+
+    fn termination(&self, state: &ChessState) -> String {
+        return match state.board.status() {
+            BoardStatus::Checkmate => "checkmate".to_string(),
+            BoardStatus::Stalemate => "stalemate".to_string(),
+            BoardStatus::Ongoing => {
+                if state.halfmove_clock >= FIFTY_MOVE_HALFMOVES {
+                    "fifty-move rule".to_string()
+                } else if state.repetitions() >= 3 {
+                    "threefold repetition".to_string()
+                } else if insufficient_material(&state.board) {
+                    "insufficient material".to_string()
+                } else {
+                    "move limit".to_string()
+                }
+            }
+        };
+    }
+
+    fn export_match(
+        &self,
+        result: &core::MatchResult,
+        event: &str,
+        round: u64,
+        player1: &str,
+        player2: &str,
+    ) -> Option<String> {
+        return Some(pgn(result, event, round, player1, player2));
+    }
 }
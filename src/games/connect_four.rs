@@ -0,0 +1,369 @@
+use std::io;
+
+use crate::core;
+use crate::core::Game;
+use crate::playground;
+use crate::strategy;
+
+pub const WIDTH: usize = 7;
+pub const HEIGHT: usize = 6;
+
+// Each column takes HEIGHT + 1 bits, bottom cell first. The extra bit stays empty so that
+// shifting a line of stones never carries it into the next column.
+const COLUMN_BITS: usize = HEIGHT + 1;
+
+const fn bottom_mask() -> u64 {
+    let mut mask = 0;
+    let mut col = 0;
+    while col < WIDTH {
+        mask |= 1 << (col * COLUMN_BITS);
+        col += 1;
+    }
+    return mask;
+}
+
+const BOTTOM_MASK: u64 = bottom_mask();
+const BOARD_MASK: u64 = BOTTOM_MASK * ((1 << HEIGHT) - 1);
+
+// Distance between neighbouring cells vertically, horizontally and along both diagonals
+const DIRECTIONS: [usize; 4] = [1, COLUMN_BITS, COLUMN_BITS - 1, COLUMN_BITS + 1];
+
+fn column_mask(col: usize) -> u64 {
+    return ((1 << HEIGHT) - 1) << (col * COLUMN_BITS);
+}
+
+fn top_mask(col: usize) -> u64 {
+    return 1 << (HEIGHT - 1 + col * COLUMN_BITS);
+}
+
+fn cell_mask(col: usize, row: usize) -> u64 {
+    return 1 << (row + col * COLUMN_BITS);
+}
+
+fn has_four(stones: u64) -> bool {
+    for &dir in DIRECTIONS.iter() {
+        let pairs = stones & (stones >> dir);
+        if pairs & (pairs >> (2 * dir)) != 0 {
+            return true;
+        }
+    }
+
+    return false;
+}
+
+// Empty cells that would complete four in a row for stones, whether or not they can be
+// played yet
+fn winning_cells(stones: u64, mask: u64) -> u64 {
+    // Vertical lines can only be completed from above
+    let mut cells = (stones << 1) & (stones << 2) & (stones << 3);
+
+    for &dir in DIRECTIONS[1..].iter() {
+        let pairs = (stones << dir) & (stones << (2 * dir));
+        cells |= pairs & (stones << (3 * dir));
+        cells |= pairs & (stones >> dir);
+
+        let pairs = (stones >> dir) & (stones >> (2 * dir));
+        cells |= pairs & (stones << dir);
+        cells |= pairs & (stones >> (3 * dir));
+    }
+
+    return cells & (BOARD_MASK ^ mask);
+}
+
+pub struct ConnectFour {}
+
+#[derive(Clone)]
+pub struct ConnectFourState {
+    // Stones of player 1 and player 2
+    pub boards: [u64; 2],
+    pub player: core::Player,
+}
+
+#[derive(PartialEq, Eq, Clone, Hash)]
+pub struct ConnectFourAction {
+    pub column: u8,
+}
+
+impl ConnectFourState {
+    pub fn mask(&self) -> u64 {
+        return self.boards[0] | self.boards[1];
+    }
+
+    fn stones(&self, player: core::Player) -> u64 {
+        return match player {
+            core::Player::Player1 => self.boards[0],
+            core::Player::Player2 => self.boards[1],
+        };
+    }
+
+    // Cell a stone dropped in col lands on, 0 if the column is full
+    fn drop_cell(&self, col: usize) -> u64 {
+        return (self.mask() + (1 << (col * COLUMN_BITS))) & column_mask(col);
+    }
+}
+
+impl core::Game for ConnectFour {
+    type State = ConnectFourState;
+    type Action = ConnectFourAction;
+
+    fn name(&self) -> String {
+        return "Connect Four".to_string();
+    }
+
+    fn init(&self) -> Self::State {
+        return ConnectFourState {
+            boards: [0, 0],
+            player: core::Player::Player1,
+        };
+    }
+
+    fn player(&self, state: &Self::State) -> core::Player {
+        return state.player;
+    }
+
+    fn actions(&self, state: &Self::State) -> Vec<Self::Action> {
+        let mask = state.mask();
+
+        return (0..WIDTH)
+            .filter(|&col| mask & top_mask(col) == 0)
+            .map(|col| ConnectFourAction { column: col as u8 })
+            .collect();
+    }
+
+    fn play(&self, action: &Self::Action, state: &Self::State) -> Self::State {
+        let cell = state.drop_cell(action.column as usize);
+        let mut new_state = state.clone();
+
+        match state.player {
+            core::Player::Player1 => new_state.boards[0] |= cell,
+            core::Player::Player2 => new_state.boards[1] |= cell,
+        }
+        new_state.player = core::other_player(state.player);
+
+        return new_state;
+    }
+
+    fn status(&self, state: &Self::State) -> core::GameStatus {
+        if has_four(state.boards[0]) {
+            return core::GameStatus::Player1Win;
+        }
+        if has_four(state.boards[1]) {
+            return core::GameStatus::Player2Win;
+        }
+        if state.mask() == BOARD_MASK {
+            return core::GameStatus::Draw;
+        }
+
+        return core::GameStatus::InProgress;
+    }
+
+    fn state_hash(&self, state: &Self::State) -> Option<u64> {
+        // Adding the bottom row marks the first empty cell of every column, above the
+        // stones, so the sum tells where player 1's stones are and how high each column is.
+        // It is mixed so that positions differing only in the right-hand columns don't
+        // collide in a table indexed by the low bits.
+        return Some(super::mix(state.boards[0] + state.mask() + BOTTOM_MASK));
+    }
+
+    fn is_tactical(&self, state: &Self::State, action: &Self::Action) -> bool {
+        // Completing four or stopping the opponent from doing so
+        let cell = state.drop_cell(action.column as usize);
+        let mask = state.mask();
+
+        return (winning_cells(state.boards[0], mask) | winning_cells(state.boards[1], mask))
+            & cell
+            != 0;
+    }
+}
+
+pub struct ConnectFourParser {}
+
+impl core::ActionParser for ConnectFourParser {
+    type Game = ConnectFour;
+
+    fn read_action(&self) -> <ConnectFour as core::Game>::Action {
+        loop {
+            println!("Enter column [1, {}]:", WIDTH);
+
+            let mut column_str = String::new();
+            io::stdin()
+                .read_line(&mut column_str)
+                .expect("Failed to read line");
+
+            match column_str.trim().parse::<u8>() {
+                Ok(column) if column >= 1 && column as usize <= WIDTH => {
+                    return ConnectFourAction { column: column - 1 };
+                }
+                _ => continue,
+            }
+        }
+    }
+}
+
+// Score for a finished game from player's point of view, None if the game is still going
+fn terminal_score(
+    game: &ConnectFour,
+    state: &ConnectFourState,
+    player: core::Player,
+) -> Option<f64> {
+    return match (game.status(state), player) {
+        (core::GameStatus::Player1Win, core::Player::Player1) => Some(f64::INFINITY),
+        (core::GameStatus::Player2Win, core::Player::Player2) => Some(f64::INFINITY),
+        (core::GameStatus::Player2Win, core::Player::Player1) => Some(f64::NEG_INFINITY),
+        (core::GameStatus::Player1Win, core::Player::Player2) => Some(f64::NEG_INFINITY),
+        (core::GameStatus::Draw, _) => Some(0.0),
+        _ => None,
+    };
+}
+
+// Worth of one threat, against that of a line of four that is still open
+const THREAT_SCORE: f64 = 10.0;
+const OPEN_LINE_SCORE: f64 = 1.0;
+
+// Number of lines of four that contain a stone of stones and none of blockers
+fn open_lines(stones: u64, blockers: u64) -> u32 {
+    let mut count = 0;
+
+    for col in 0..WIDTH {
+        for row in 0..HEIGHT {
+            for &(dc, dr) in [(0, 1), (1, 0), (1, 1), (1, -1)].iter() {
+                let end_col = col as i32 + 3 * dc;
+                let end_row = row as i32 + 3 * dr;
+                if end_col >= WIDTH as i32 || end_row < 0 || end_row >= HEIGHT as i32 {
+                    continue;
+                }
+
+                let line = (0..4).fold(0, |line, i| {
+                    let c = (col as i32 + i * dc) as usize;
+                    let r = (row as i32 + i * dr) as usize;
+                    return line | cell_mask(c, r);
+                });
+
+                if line & blockers == 0 && line & stones != 0 {
+                    count += 1;
+                }
+            }
+        }
+    }
+
+    return count;
+}
+
+pub struct ConnectFourHeuristic {}
+
+impl strategy::Heuristic<ConnectFour> for ConnectFourHeuristic {
+    fn name(&self) -> String {
+        return "threats".to_string();
+    }
+
+    fn evaluate(&self, game: &ConnectFour, state: &ConnectFourState, player: core::Player) -> f64 {
+        if let Some(score) = terminal_score(game, state, player) {
+            return score;
+        }
+
+        let ours = state.stones(player);
+        let theirs = state.stones(core::other_player(player));
+        let mask = state.mask();
+
+        // Threats are empty cells that would complete four, they decide most games once the
+        // board fills up. Lines that are still open break ties early on.
+        let threats = winning_cells(ours, mask).count_ones() as f64
+            - winning_cells(theirs, mask).count_ones() as f64;
+        let lines = open_lines(ours, theirs) as f64 - open_lines(theirs, ours) as f64;
+
+        return THREAT_SCORE * threats + OPEN_LINE_SCORE * lines;
+    }
+}
+
+// Central columns take part in the most lines. The bias is only a hint, killer moves and
+// history still come first.
+pub struct ConnectFourOrdering {}
+
+impl strategy::ActionOrdering<ConnectFour> for ConnectFourOrdering {
+    fn priority(
+        &self,
+        _game: &ConnectFour,
+        _state: &ConnectFourState,
+        action: &ConnectFourAction,
+    ) -> i32 {
+        let center = WIDTH as i32 / 2;
+        return center - (action.column as i32 - center).abs();
+    }
+}
+
+fn heuristics() -> Vec<Box<dyn strategy::Heuristic<ConnectFour>>> {
+    return vec![Box::new(ConnectFourHeuristic {})];
+}
+
+impl playground::PlaygroundUtils for ConnectFour {
+    fn strategies(&self) -> Vec<Box<dyn core::Strategy<Self>>> {
+        return vec![
+            Box::new(strategy::HumanStrategy {
+                parser: ConnectFourParser {},
+            }),
+            Box::new(strategy::RandomStrategy::new()),
+            Box::new(
+                strategy::MinMaxStrategy::new(heuristics(), 5, false)
+                    .with_ordering(Box::new(ConnectFourOrdering {})),
+            ),
+            Box::new(
+                strategy::MinMaxStrategy::new(heuristics(), 8, true)
+                    .with_ordering(Box::new(ConnectFourOrdering {})),
+            ),
+            Box::new(strategy::MCTSStrategy::new()),
+        ];
+    }
+
+    fn serialize_state(&self, state: &ConnectFourState) -> String {
+        let mut rows = Vec::new();
+
+        for row in (0..HEIGHT).rev() {
+            let cells: Vec<&str> = (0..WIDTH)
+                .map(|col| {
+                    let cell = cell_mask(col, row);
+                    if state.boards[0] & cell != 0 {
+                        return "X";
+                    }
+                    if state.boards[1] & cell != 0 {
+                        return "O";
+                    }
+                    return ".";
+                })
+                .collect();
+            rows.push(cells.join(" "));
+        }
+
+        let columns: Vec<String> = (1..=WIDTH).map(|col| col.to_string()).collect();
+        rows.push(columns.join(" "));
+
+        return rows.join("\n");
+    }
+
+    // Positions are written as the columns played so far, numbered from 1, e.g. "4453"
+    fn parse_state(&self, s: &str) -> Option<ConnectFourState> {
+        let mut state = self.init();
+
+        for c in s.trim().chars() {
+            if self.status(&state) != core::GameStatus::InProgress {
+                return None;
+            }
+
+            let column = c.to_digit(10)? as u8;
+            if column < 1 || column as usize > WIDTH {
+                return None;
+            }
+
+            let action = ConnectFourAction { column: column - 1 };
+            if !self.actions(&state).contains(&action) {
+                return None;
+            }
+            state = self.play(&action, &state);
+        }
+
+        return Some(state);
+    }
+
+    fn serialize_action(&self, _state: &ConnectFourState, action: &ConnectFourAction) -> String {
+        return (action.column + 1).to_string();
+    }
+}
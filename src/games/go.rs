@@ -0,0 +1,460 @@
+use std::io;
+
+use crate::core;
+use crate::core::Game;
+use crate::playground;
+use crate::strategy;
+
+pub const DEFAULT_SIZE: usize = 9;
+pub const DEFAULT_KOMI: f64 = 7.5;
+
+// Column letters of the usual coordinates, I is skipped so it can't be taken for J
+const COLUMNS: &str = "ABCDEFGHJKLMNOPQRST";
+pub const MAX_SIZE: usize = 19;
+
+pub struct Go {
+    pub size: usize,
+    // Points given to white for moving second
+    pub komi: f64,
+    // Games still going after this many moves, passes included, are scored as they stand
+    pub max_moves: Option<u32>,
+}
+
+impl Go {
+    pub fn new() -> Go {
+        return Go {
+            size: DEFAULT_SIZE,
+            komi: DEFAULT_KOMI,
+            max_moves: None,
+        };
+    }
+
+    pub fn with_size(mut self, size: usize) -> Go {
+        assert!(
+            (2..=MAX_SIZE).contains(&size),
+            "Board size must be between 2 and {}",
+            MAX_SIZE
+        );
+        self.size = size;
+        return self;
+    }
+
+    pub fn with_komi(mut self, komi: f64) -> Go {
+        self.komi = komi;
+        return self;
+    }
+
+    pub fn with_max_moves(mut self, max_moves: u32) -> Go {
+        self.max_moves = Some(max_moves);
+        return self;
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum GoCell {
+    Empty,
+    Black,
+    White,
+}
+
+#[derive(Clone)]
+pub struct GoState {
+    // Points row by row, starting from the bottom left corner
+    pub board: Vec<GoCell>,
+    pub player: core::Player,
+    // Passes since the last stone was placed
+    pub passes: u8,
+    pub num_moves: u32,
+    pub hash: u64,
+    // Hashes of every earlier position, a move may not recreate one of them
+    pub history: Vec<u64>,
+}
+
+#[derive(PartialEq, Eq, Clone, Hash)]
+pub enum GoAction {
+    Place(u16),
+    Pass,
+}
+
+fn player_cell(player: core::Player) -> GoCell {
+    return match player {
+        core::Player::Player1 => GoCell::Black,
+        core::Player::Player2 => GoCell::White,
+    };
+}
+
+// Zobrist key of a stone of color on point, derived from both rather than looked up so
+// that any board size works
+fn stone_key(point: usize, color: GoCell) -> u64 {
+    return super::mix(point as u64 * 2 + (color == GoCell::White) as u64 + 1);
+}
+
+impl Go {
+    // Points next to point, None past the edges
+    fn neighbors(&self, point: usize) -> [Option<usize>; 4] {
+        let size = self.size;
+        let (row, col) = (point / size, point % size);
+
+        return [
+            (row > 0).then(|| point - size),
+            (row + 1 < size).then(|| point + size),
+            (col > 0).then(|| point - 1),
+            (col + 1 < size).then(|| point + 1),
+        ];
+    }
+
+    // Stones of the color group reached from start, empty if the group has a liberty. The
+    // point filled counts as occupied, so that a move can be checked without playing it.
+    fn dead_group(
+        &self,
+        board: &[GoCell],
+        start: usize,
+        color: GoCell,
+        filled: usize,
+    ) -> Vec<usize> {
+        let mut group = vec![start];
+        let mut idx = 0;
+
+        // Groups are small enough for a linear search to beat allocating a visited set
+        while idx < group.len() {
+            for &neighbor in self.neighbors(group[idx]).iter().flatten() {
+                if board[neighbor] == GoCell::Empty && neighbor != filled {
+                    return Vec::new();
+                }
+                if board[neighbor] == color && !group.contains(&neighbor) {
+                    group.push(neighbor);
+                }
+            }
+            idx += 1;
+        }
+
+        return group;
+    }
+
+    // Hash of the position after player places a stone on point and the stones that
+    // captures, None if the move is suicide or repeats an earlier position (positional
+    // superko, which covers simple ko)
+    fn place(&self, state: &GoState, point: usize) -> Option<(u64, Vec<usize>)> {
+        if state.board[point] != GoCell::Empty {
+            return None;
+        }
+
+        let color = player_cell(state.player);
+        let opponent = player_cell(core::other_player(state.player));
+        let mut hash = state.hash ^ stone_key(point, color);
+        let mut captured: Vec<usize> = Vec::new();
+
+        for &neighbor in self.neighbors(point).iter().flatten() {
+            if state.board[neighbor] != opponent || captured.contains(&neighbor) {
+                continue;
+            }
+
+            for stone in self.dead_group(&state.board, neighbor, opponent, point) {
+                hash ^= stone_key(stone, opponent);
+                captured.push(stone);
+            }
+        }
+
+        if captured.is_empty()
+            && !self
+                .dead_group(&state.board, point, color, point)
+                .is_empty()
+        {
+            return None;
+        }
+
+        if state.history.contains(&hash) {
+            return None;
+        }
+
+        return Some((hash, captured));
+    }
+
+    // Tromp-Taylor area: stones of color plus the empty points that only reach color
+    fn area(&self, board: &[GoCell], color: GoCell) -> usize {
+        let mut visited = vec![false; board.len()];
+        let mut area = 0;
+
+        for point in 0..board.len() {
+            if board[point] == color {
+                area += 1;
+            }
+            if board[point] != GoCell::Empty || visited[point] {
+                continue;
+            }
+
+            // Flood the empty region and note which colors border it
+            let mut region = vec![point];
+            let mut reaches_color = false;
+            let mut reaches_other = false;
+            let mut idx = 0;
+            visited[point] = true;
+
+            while idx < region.len() {
+                for &neighbor in self.neighbors(region[idx]).iter().flatten() {
+                    match board[neighbor] {
+                        GoCell::Empty => {
+                            if !visited[neighbor] {
+                                visited[neighbor] = true;
+                                region.push(neighbor);
+                            }
+                        }
+                        c if c == color => reaches_color = true,
+                        _ => reaches_other = true,
+                    }
+                }
+                idx += 1;
+            }
+
+            if reaches_color && !reaches_other {
+                area += region.len();
+            }
+        }
+
+        return area;
+    }
+
+    // Black's area minus white's area and komi
+    pub fn score(&self, state: &GoState) -> f64 {
+        return self.area(&state.board, GoCell::Black) as f64
+            - self.area(&state.board, GoCell::White) as f64
+            - self.komi;
+    }
+
+    fn point_name(&self, point: usize) -> String {
+        let col = COLUMNS.as_bytes()[point % self.size] as char;
+        return format!("{}{}", col, point / self.size + 1);
+    }
+
+    fn action_from_string(&self, s: &str) -> Option<GoAction> {
+        let s = s.trim().to_uppercase();
+
+        if s == "PASS" {
+            return Some(GoAction::Pass);
+        }
+
+        let mut chars = s.chars();
+        let col = COLUMNS[..self.size].find(chars.next()?)?;
+        let row: usize = chars.as_str().parse().ok()?;
+        if row < 1 || row > self.size {
+            return None;
+        }
+
+        return Some(GoAction::Place(((row - 1) * self.size + col) as u16));
+    }
+}
+
+impl core::Game for Go {
+    type State = GoState;
+    type Action = GoAction;
+
+    fn name(&self) -> String {
+        return "Go".to_string();
+    }
+
+    fn init(&self) -> Self::State {
+        return GoState {
+            board: vec![GoCell::Empty; self.size * self.size],
+            player: core::Player::Player1,
+            passes: 0,
+            num_moves: 0,
+            hash: 0,
+            history: Vec::new(),
+        };
+    }
+
+    fn player(&self, state: &Self::State) -> core::Player {
+        return state.player;
+    }
+
+    fn actions(&self, state: &Self::State) -> Vec<Self::Action> {
+        let mut actions: Vec<GoAction> = (0..state.board.len())
+            .filter(|&point| self.place(state, point).is_some())
+            .map(|point| GoAction::Place(point as u16))
+            .collect();
+        actions.push(GoAction::Pass);
+
+        return actions;
+    }
+
+    fn play(&self, action: &Self::Action, state: &Self::State) -> Self::State {
+        let mut new_state = state.clone();
+        new_state.player = core::other_player(state.player);
+        new_state.num_moves += 1;
+
+        match action {
+            GoAction::Pass => new_state.passes += 1,
+            GoAction::Place(point) => {
+                let (hash, captured) = self.place(state, *point as usize).expect("Illegal move");
+
+                new_state.board[*point as usize] = player_cell(state.player);
+                for stone in captured {
+                    new_state.board[stone] = GoCell::Empty;
+                }
+                new_state.history.push(state.hash);
+                new_state.hash = hash;
+                new_state.passes = 0;
+            }
+        }
+
+        return new_state;
+    }
+
+    fn status(&self, state: &Self::State) -> core::GameStatus {
+        // The game ends after two passes in a row, or is cut short at the move limit
+        let limit_reached = self.max_moves.is_some_and(|max| state.num_moves >= max);
+        if state.passes < 2 && !limit_reached {
+            return core::GameStatus::InProgress;
+        }
+
+        let score = self.score(state);
+
+        if score > 0.0 {
+            return core::GameStatus::Player1Win;
+        }
+        if score < 0.0 {
+            return core::GameStatus::Player2Win;
+        }
+
+        return core::GameStatus::Draw;
+    }
+
+    fn state_hash(&self, state: &Self::State) -> Option<u64> {
+        // A pass brings the game closer to its end without changing the board
+        return Some(state.hash ^ state.passes as u64);
+    }
+}
+
+pub struct GoParser {
+    pub size: usize,
+}
+
+impl core::ActionParser for GoParser {
+    type Game = Go;
+
+    fn read_action(&self) -> <Go as core::Game>::Action {
+        let game = Go::new().with_size(self.size);
+
+        loop {
+            println!("Enter [column][row] or pass [e.g. E5]");
+
+            let mut move_str = String::new();
+            io::stdin()
+                .read_line(&mut move_str)
+                .expect("Failed to read line");
+
+            if let Some(action) = game.action_from_string(&move_str) {
+                return action;
+            }
+        }
+    }
+}
+
+// Score for a finished game from player's point of view, None if the game is still going
+fn terminal_score(game: &Go, state: &GoState, player: core::Player) -> Option<f64> {
+    return match (game.status(state), player) {
+        (core::GameStatus::Player1Win, core::Player::Player1) => Some(f64::INFINITY),
+        (core::GameStatus::Player2Win, core::Player::Player2) => Some(f64::INFINITY),
+        (core::GameStatus::Player2Win, core::Player::Player1) => Some(f64::NEG_INFINITY),
+        (core::GameStatus::Player1Win, core::Player::Player2) => Some(f64::NEG_INFINITY),
+        (core::GameStatus::Draw, _) => Some(0.0),
+        _ => None,
+    };
+}
+
+// Area score lead as if the game ended now
+pub struct GoHeuristic {}
+
+impl strategy::Heuristic<Go> for GoHeuristic {
+    fn name(&self) -> String {
+        return "area".to_string();
+    }
+
+    fn evaluate(&self, game: &Go, state: &GoState, player: core::Player) -> f64 {
+        if let Some(score) = terminal_score(game, state, player) {
+            return score;
+        }
+
+        return match player {
+            core::Player::Player1 => game.score(state),
+            core::Player::Player2 => -game.score(state),
+        };
+    }
+}
+
+fn heuristics() -> Vec<Box<dyn strategy::Heuristic<Go>>> {
+    return vec![Box::new(GoHeuristic {})];
+}
+
+impl playground::PlaygroundUtils for Go {
+    fn strategies(&self) -> Vec<Box<dyn core::Strategy<Self>>> {
+        // The board is too wide for minimax to see past a couple of moves, it is only here
+        // as a baseline for MCTS
+        return vec![
+            Box::new(strategy::HumanStrategy {
+                parser: GoParser { size: self.size },
+            }),
+            Box::new(strategy::RandomStrategy::new()),
+            Box::new(strategy::MinMaxStrategy::new(heuristics(), 1, false)),
+            Box::new(strategy::MinMaxStrategy::new(heuristics(), 2, true)),
+            Box::new(strategy::MCTSStrategy::new()),
+        ];
+    }
+
+    fn serialize_state(&self, state: &GoState) -> String {
+        let columns: Vec<String> = COLUMNS[..self.size]
+            .chars()
+            .map(|c| c.to_string())
+            .collect();
+        let header = format!("   {}", columns.join(" "));
+        let mut rows = vec![header.clone()];
+
+        for row in (0..self.size).rev() {
+            let cells: Vec<&str> = (0..self.size)
+                .map(|col| match state.board[row * self.size + col] {
+                    GoCell::Black => "X",
+                    GoCell::White => "O",
+                    GoCell::Empty => ".",
+                })
+                .collect();
+            rows.push(format!("{:>2} {} {}", row + 1, cells.join(" "), row + 1));
+        }
+        rows.push(header);
+
+        return rows.join("\n");
+    }
+
+    // Positions are written as the moves played from the empty board, e.g. "E5 C3 pass"
+    fn parse_state(&self, s: &str) -> Option<GoState> {
+        let mut state = self.init();
+
+        for token in s.split_whitespace() {
+            if self.status(&state) != core::GameStatus::InProgress {
+                return None;
+            }
+
+            let action = self.action_from_string(token)?;
+            if !self.actions(&state).contains(&action) {
+                return None;
+            }
+            state = self.play(&action, &state);
+        }
+
+        return Some(state);
+    }
+
+    fn serialize_action(&self, _state: &GoState, action: &GoAction) -> String {
+        return match action {
+            GoAction::Place(point) => self.point_name(*point as usize),
+            GoAction::Pass => "pass".to_string(),
+        };
+    }
+
+    fn termination(&self, state: &GoState) -> String {
+        if state.passes >= 2 {
+            return "normal".to_string();
+        }
+
+        return "move limit".to_string();
+    }
+}
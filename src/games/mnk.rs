@@ -0,0 +1,424 @@
+use std::fmt;
+use std::io;
+
+use crate::core;
+use crate::core::Game;
+use crate::playground;
+use crate::strategy;
+
+// Columns are lettered, so boards can't be wider than the alphabet
+pub const MAX_WIDTH: usize = 26;
+
+// Actions number cells with a u16
+pub const MAX_CELLS: usize = u16::MAX as usize + 1;
+
+// Horizontal, vertical and both diagonal directions as (column, row) steps
+const DIRECTIONS: [(i32, i32); 4] = [(1, 0), (0, 1), (1, 1), (1, -1)];
+
+// Players take turns placing stones on an m x n board, the first to get k in a row wins
+pub struct MnkGame {
+    // Columns
+    pub m: usize,
+    // Rows
+    pub n: usize,
+    pub k: usize,
+}
+
+impl MnkGame {
+    pub fn new(m: usize, n: usize, k: usize) -> MnkGame {
+        assert!(
+            (1..=MAX_WIDTH).contains(&m) && n >= 1,
+            "Board must have 1 to {} columns and at least 1 row",
+            MAX_WIDTH
+        );
+        assert!(
+            m * n <= MAX_CELLS,
+            "Board must have at most {} cells",
+            MAX_CELLS
+        );
+        assert!(
+            k >= 1 && k <= m.max(n),
+            "k must be between 1 and the longer side"
+        );
+
+        return MnkGame { m: m, n: n, k: k };
+    }
+
+    pub fn tic_tac_toe() -> MnkGame {
+        return MnkGame::new(3, 3, 3);
+    }
+
+    pub fn gomoku() -> MnkGame {
+        return MnkGame::new(15, 15, 5);
+    }
+}
+
+#[derive(PartialEq, Eq, Copy, Clone)]
+pub enum MnkCell {
+    X,
+    O,
+    Empty,
+}
+
+impl fmt::Display for MnkCell {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MnkCell::X => write!(f, "X"),
+            MnkCell::O => write!(f, "O"),
+            MnkCell::Empty => write!(f, "."),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct MnkState {
+    // Cells row by row, starting from the bottom left corner
+    pub board: Vec<MnkCell>,
+    pub player: core::Player,
+    pub num_moves: usize,
+    // Set by the move that completes k in a row, so the board is never scanned for lines
+    pub winner: Option<core::Player>,
+    pub hash: u64,
+}
+
+#[derive(PartialEq, Eq, Clone, Hash)]
+pub struct MnkAction {
+    pub cell: u16,
+}
+
+fn player_cell(player: core::Player) -> MnkCell {
+    return match player {
+        core::Player::Player1 => MnkCell::X,
+        core::Player::Player2 => MnkCell::O,
+    };
+}
+
+fn stone_key(cell: usize, player: core::Player) -> u64 {
+    return super::mix(cell as u64 * 2 + (player == core::Player::Player2) as u64 + 1);
+}
+
+impl MnkGame {
+    // Cell reached from cell after steps steps in direction, None past the edges
+    fn step(&self, cell: usize, (dc, dr): (i32, i32), steps: i32) -> Option<usize> {
+        let col = (cell % self.m) as i32 + dc * steps;
+        let row = (cell / self.m) as i32 + dr * steps;
+
+        if col < 0 || col >= self.m as i32 || row < 0 || row >= self.n as i32 {
+            return None;
+        }
+
+        return Some(row as usize * self.m + col as usize);
+    }
+
+    // Stones of color in a row starting next to cell and going in direction
+    fn run_length(&self, board: &[MnkCell], cell: usize, dir: (i32, i32), color: MnkCell) -> usize {
+        let mut length = 0;
+
+        while let Some(next) = self.step(cell, dir, length as i32 + 1) {
+            if board[next] != color {
+                break;
+            }
+            length += 1;
+        }
+
+        return length;
+    }
+
+    // First cell and direction of every line of k cells on the board
+    fn lines(&self) -> impl Iterator<Item = (usize, (i32, i32))> + '_ {
+        return (0..self.m * self.n).flat_map(move |cell| {
+            return DIRECTIONS
+                .iter()
+                .filter(move |&&dir| self.step(cell, dir, self.k as i32 - 1).is_some())
+                .map(move |&dir| (cell, dir));
+        });
+    }
+
+    // Number of lines of k cells through cell
+    fn lines_through(&self, cell: usize) -> usize {
+        let k = self.k as i32;
+        let mut count = 0;
+
+        for &dir in DIRECTIONS.iter() {
+            for offset in 0..k {
+                if let Some(start) = self.step(cell, dir, -offset) {
+                    if self.step(start, dir, k - 1).is_some() {
+                        count += 1;
+                    }
+                }
+            }
+        }
+
+        return count;
+    }
+
+    fn cell_name(&self, cell: usize) -> String {
+        return format!(
+            "{}{}",
+            (b'a' + (cell % self.m) as u8) as char,
+            cell / self.m + 1
+        );
+    }
+
+    fn action_from_string(&self, s: &str) -> Option<MnkAction> {
+        let s = s.trim().to_lowercase();
+        let mut chars = s.chars();
+
+        let col = chars.next()? as usize;
+        if col < 'a' as usize || col >= 'a' as usize + self.m {
+            return None;
+        }
+
+        let row: usize = chars.as_str().parse().ok()?;
+        if row < 1 || row > self.n {
+            return None;
+        }
+
+        return Some(MnkAction {
+            cell: ((row - 1) * self.m + col - 'a' as usize) as u16,
+        });
+    }
+}
+
+impl core::Game for MnkGame {
+    type State = MnkState;
+    type Action = MnkAction;
+
+    fn name(&self) -> String {
+        return match (self.m, self.n, self.k) {
+            (3, 3, 3) => "Tic-Tac-Toe".to_string(),
+            (15, 15, 5) => "Gomoku".to_string(),
+            (m, n, k) => format!("{},{},{}-game", m, n, k),
+        };
+    }
+
+    fn init(&self) -> Self::State {
+        return MnkState {
+            board: vec![MnkCell::Empty; self.m * self.n],
+            player: core::Player::Player1,
+            num_moves: 0,
+            winner: None,
+            hash: 0,
+        };
+    }
+
+    fn player(&self, state: &Self::State) -> core::Player {
+        return state.player;
+    }
+
+    fn actions(&self, state: &Self::State) -> Vec<Self::Action> {
+        return (0..state.board.len())
+            .filter(|&cell| state.board[cell] == MnkCell::Empty)
+            .map(|cell| MnkAction { cell: cell as u16 })
+            .collect();
+    }
+
+    fn play(&self, action: &Self::Action, state: &Self::State) -> Self::State {
+        let cell = action.cell as usize;
+        let color = player_cell(state.player);
+        let mut new_state = state.clone();
+
+        new_state.board[cell] = color;
+        new_state.player = core::other_player(state.player);
+        new_state.num_moves += 1;
+        new_state.hash ^= stone_key(cell, state.player);
+
+        // Only lines through the new stone can have been completed
+        for &(dc, dr) in DIRECTIONS.iter() {
+            let length = 1
+                + self.run_length(&new_state.board, cell, (dc, dr), color)
+                + self.run_length(&new_state.board, cell, (-dc, -dr), color);
+
+            if length >= self.k {
+                new_state.winner = Some(state.player);
+                break;
+            }
+        }
+
+        return new_state;
+    }
+
+    fn status(&self, state: &Self::State) -> core::GameStatus {
+        return match state.winner {
+            Some(core::Player::Player1) => core::GameStatus::Player1Win,
+            Some(core::Player::Player2) => core::GameStatus::Player2Win,
+            None if state.num_moves == state.board.len() => core::GameStatus::Draw,
+            None => core::GameStatus::InProgress,
+        };
+    }
+
+    fn state_hash(&self, state: &Self::State) -> Option<u64> {
+        return Some(state.hash);
+    }
+}
+
+pub struct MnkParser {
+    pub m: usize,
+    pub n: usize,
+}
+
+impl core::ActionParser for MnkParser {
+    type Game = MnkGame;
+
+    fn read_action(&self) -> <MnkGame as core::Game>::Action {
+        let game = MnkGame::new(self.m, self.n, 1);
+
+        loop {
+            println!("Enter [column][row] [e.g. b2]");
+
+            let mut cell_str = String::new();
+            io::stdin()
+                .read_line(&mut cell_str)
+                .expect("Failed to read line");
+
+            if let Some(action) = game.action_from_string(&cell_str) {
+                return action;
+            }
+        }
+    }
+}
+
+// Score for a finished game from player's point of view, None if the game is still going
+fn terminal_score(game: &MnkGame, state: &MnkState, player: core::Player) -> Option<f64> {
+    return match (game.status(state), player) {
+        (core::GameStatus::Player1Win, core::Player::Player1) => Some(f64::INFINITY),
+        (core::GameStatus::Player2Win, core::Player::Player2) => Some(f64::INFINITY),
+        (core::GameStatus::Player2Win, core::Player::Player1) => Some(f64::NEG_INFINITY),
+        (core::GameStatus::Player1Win, core::Player::Player2) => Some(f64::NEG_INFINITY),
+        (core::GameStatus::Draw, _) => Some(1.0),
+        _ => None,
+    };
+}
+
+pub struct MnkHeuristic {}
+
+impl strategy::Heuristic<MnkGame> for MnkHeuristic {
+    fn name(&self) -> String {
+        return "outcome".to_string();
+    }
+
+    fn evaluate(&self, game: &MnkGame, state: &MnkState, player: core::Player) -> f64 {
+        // Only finished games are scored, every other position is neutral
+        return terminal_score(game, state, player).unwrap_or(0.0);
+    }
+}
+
+pub struct MnkLinesHeuristic {}
+
+impl strategy::Heuristic<MnkGame> for MnkLinesHeuristic {
+    fn name(&self) -> String {
+        return "lines".to_string();
+    }
+
+    fn evaluate(&self, game: &MnkGame, state: &MnkState, player: core::Player) -> f64 {
+        if let Some(score) = terminal_score(game, state, player) {
+            return score;
+        }
+
+        let ours = player_cell(player);
+
+        // Difference between the number of lines still open to us and to our opponent
+        let mut score = 0;
+
+        for (start, dir) in game.lines() {
+            let mut has_ours = false;
+            let mut has_theirs = false;
+
+            for steps in 0..game.k as i32 {
+                match state.board[game.step(start, dir, steps).unwrap()] {
+                    MnkCell::Empty => {}
+                    c if c == ours => has_ours = true,
+                    _ => has_theirs = true,
+                }
+            }
+
+            if has_ours && !has_theirs {
+                score += 1;
+            } else if has_theirs && !has_ours {
+                score -= 1;
+            }
+        }
+
+        return score as f64;
+    }
+}
+
+// Cells on more lines are searched first, e.g. the center of tic-tac-toe is part of four
+// lines, corners of three and edges of two
+pub struct MnkOrdering {}
+
+impl strategy::ActionOrdering<MnkGame> for MnkOrdering {
+    fn priority(&self, game: &MnkGame, _state: &MnkState, action: &MnkAction) -> i32 {
+        return game.lines_through(action.cell as usize) as i32;
+    }
+}
+
+fn heuristics() -> Vec<Box<dyn strategy::Heuristic<MnkGame>>> {
+    return vec![Box::new(MnkHeuristic {}), Box::new(MnkLinesHeuristic {})];
+}
+
+impl playground::PlaygroundUtils for MnkGame {
+    fn strategies(&self) -> Vec<Box<dyn core::Strategy<Self>>> {
+        // Tic-tac-toe can be searched to the end, larger boards only a couple of moves deep
+        let depth = if self.m * self.n <= 9 { 10 } else { 2 };
+
+        return vec![
+            Box::new(strategy::HumanStrategy {
+                parser: MnkParser {
+                    m: self.m,
+                    n: self.n,
+                },
+            }),
+            Box::new(strategy::RandomStrategy::new()),
+            Box::new(
+                strategy::MinMaxStrategy::new(heuristics(), depth, false)
+                    .with_ordering(Box::new(MnkOrdering {})),
+            ),
+            Box::new(
+                strategy::MinMaxStrategy::new(heuristics(), depth, true)
+                    .with_ordering(Box::new(MnkOrdering {})),
+            ),
+            Box::new(strategy::MCTSStrategy::new()),
+        ];
+    }
+
+    fn serialize_state(&self, state: &MnkState) -> String {
+        let columns: Vec<String> = (0..self.m)
+            .map(|col| ((b'a' + col as u8) as char).to_string())
+            .collect();
+        let mut rows = vec![format!("   {}", columns.join(" "))];
+
+        for row in (0..self.n).rev() {
+            let cells: Vec<String> = state.board[row * self.m..(row + 1) * self.m]
+                .iter()
+                .map(|cell| cell.to_string())
+                .collect();
+            rows.push(format!("{:>2} {}", row + 1, cells.join(" ")));
+        }
+
+        return rows.join("\n");
+    }
+
+    // Positions are written as the cells played from the empty board, e.g. "h8 h9 i7"
+    fn parse_state(&self, s: &str) -> Option<MnkState> {
+        let mut state = self.init();
+
+        for token in s.split_whitespace() {
+            if self.status(&state) != core::GameStatus::InProgress {
+                return None;
+            }
+
+            let action = self.action_from_string(token)?;
+            if state.board[action.cell as usize] != MnkCell::Empty {
+                return None;
+            }
+            state = self.play(&action, &state);
+        }
+
+        return Some(state);
+    }
+
+    fn serialize_action(&self, _state: &MnkState, action: &MnkAction) -> String {
+        return self.cell_name(action.cell as usize);
+    }
+}
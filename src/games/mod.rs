@@ -1,5 +1,23 @@
-mod tic_tac_toe;
-pub use tic_tac_toe::*;
-
 mod chess;
 pub use self::chess::*;
+
+mod connect_four;
+pub use self::connect_four::*;
+
+mod othello;
+pub use self::othello::*;
+
+mod go;
+pub use self::go::*;
+
+mod mnk;
+pub use self::mnk::*;
+
+// Scrambles x so that nearby values end up far apart (the splitmix64 mixer), for hashing
+// positions of games that have no standard hash
+fn mix(x: u64) -> u64 {
+    let mut z = x.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    return z ^ (z >> 31);
+}
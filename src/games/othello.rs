@@ -0,0 +1,388 @@
+use std::io;
+
+use crate::core;
+use crate::core::Game;
+use crate::playground;
+use crate::strategy;
+
+// Squares are numbered a1 = 0, b1 = 1, ..., h8 = 63. As is usual for Othello, rank 1 is
+// drawn at the top of the board.
+const FILE_A: u64 = 0x0101_0101_0101_0101;
+const FILE_H: u64 = FILE_A << 7;
+const CORNERS: u64 = 0x8100_0000_0000_0081;
+
+// Marks positions with white to move in state_hash
+const PLAYER2_KEY: u64 = 0xD6E8_FEB8_6659_FD93;
+
+// Squares diagonally next to a corner, taking one usually hands the corner to the opponent
+const X_SQUARES: u64 = 0x0042_0000_0000_4200;
+
+// Shifts towards north, south, east, west and the four diagonals, with the files a stone
+// can't come from without wrapping around the board
+const DIRECTIONS: [(i32, u64); 8] = [
+    (8, 0),
+    (-8, 0),
+    (1, FILE_H),
+    (-1, FILE_A),
+    (9, FILE_H),
+    (7, FILE_A),
+    (-7, FILE_H),
+    (-9, FILE_A),
+];
+
+fn shift(stones: u64, (dir, edge): (i32, u64)) -> u64 {
+    let stones = stones & !edge;
+
+    if dir > 0 {
+        return stones << dir;
+    }
+    return stones >> -dir;
+}
+
+// Empty squares where own can place a disc, flanking a line of opp's discs
+fn legal_moves(own: u64, opp: u64) -> u64 {
+    let empty = !(own | opp);
+    let mut moves = 0;
+
+    for &dir in DIRECTIONS.iter() {
+        let mut line = shift(own, dir) & opp;
+        for _ in 0..5 {
+            line |= shift(line, dir) & opp;
+        }
+        moves |= shift(line, dir) & empty;
+    }
+
+    return moves;
+}
+
+// Discs of opp flipped by own placing a disc on square
+fn flips(own: u64, opp: u64, square: u64) -> u64 {
+    let mut flipped = 0;
+
+    for &dir in DIRECTIONS.iter() {
+        let mut line = 0;
+        let mut cur = shift(square, dir);
+
+        while cur & opp != 0 {
+            line |= cur;
+            cur = shift(cur, dir);
+        }
+
+        if cur & own != 0 {
+            flipped |= line;
+        }
+    }
+
+    return flipped;
+}
+
+fn square_name(square: u8) -> String {
+    return format!("{}{}", (b'a' + square % 8) as char, square / 8 + 1);
+}
+
+fn square_from_string(s: &str) -> Option<u8> {
+    let mut chars = s.chars();
+    let file = chars.next()?;
+    let rank = chars.next()?;
+
+    if chars.next().is_some() || !('a'..='h').contains(&file) || !('1'..='8').contains(&rank) {
+        return None;
+    }
+
+    return Some((rank as u8 - b'1') * 8 + (file as u8 - b'a'));
+}
+
+pub struct Othello {}
+
+#[derive(Clone)]
+pub struct OthelloState {
+    // Discs of player 1 (black) and player 2 (white)
+    pub boards: [u64; 2],
+    pub player: core::Player,
+}
+
+#[derive(PartialEq, Eq, Clone, Hash)]
+pub enum OthelloAction {
+    Place(u8),
+    // Only legal, and then the only action, when the player has nowhere to place a disc
+    Pass,
+}
+
+impl OthelloState {
+    fn discs(&self, player: core::Player) -> u64 {
+        return match player {
+            core::Player::Player1 => self.boards[0],
+            core::Player::Player2 => self.boards[1],
+        };
+    }
+
+    fn moves(&self, player: core::Player) -> u64 {
+        return legal_moves(self.discs(player), self.discs(core::other_player(player)));
+    }
+}
+
+fn action_from_string(s: &str) -> Option<OthelloAction> {
+    let s = s.trim().to_lowercase();
+
+    if s == "pass" {
+        return Some(OthelloAction::Pass);
+    }
+
+    return square_from_string(&s).map(OthelloAction::Place);
+}
+
+impl core::Game for Othello {
+    type State = OthelloState;
+    type Action = OthelloAction;
+
+    fn name(&self) -> String {
+        return "Othello".to_string();
+    }
+
+    fn init(&self) -> Self::State {
+        // Black on d5 and e4, white on d4 and e5
+        return OthelloState {
+            boards: [(1 << 35) | (1 << 28), (1 << 27) | (1 << 36)],
+            player: core::Player::Player1,
+        };
+    }
+
+    fn player(&self, state: &Self::State) -> core::Player {
+        return state.player;
+    }
+
+    fn actions(&self, state: &Self::State) -> Vec<Self::Action> {
+        let mut moves = state.moves(state.player);
+
+        if moves == 0 {
+            return vec![OthelloAction::Pass];
+        }
+
+        let mut actions = Vec::new();
+        while moves != 0 {
+            actions.push(OthelloAction::Place(moves.trailing_zeros() as u8));
+            moves &= moves - 1;
+        }
+
+        return actions;
+    }
+
+    fn play(&self, action: &Self::Action, state: &Self::State) -> Self::State {
+        let mut new_state = state.clone();
+        new_state.player = core::other_player(state.player);
+
+        if let OthelloAction::Place(square) = action {
+            let square = 1 << square;
+            let own = state.discs(state.player);
+            let opp = state.discs(new_state.player);
+            let flipped = flips(own, opp, square);
+
+            let (own, opp) = (own | square | flipped, opp & !flipped);
+            new_state.boards = match state.player {
+                core::Player::Player1 => [own, opp],
+                core::Player::Player2 => [opp, own],
+            };
+        }
+
+        return new_state;
+    }
+
+    fn status(&self, state: &Self::State) -> core::GameStatus {
+        // The game goes on as long as either player can place a disc
+        if state.moves(core::Player::Player1) != 0 || state.moves(core::Player::Player2) != 0 {
+            return core::GameStatus::InProgress;
+        }
+
+        let black = state.boards[0].count_ones();
+        let white = state.boards[1].count_ones();
+
+        if black > white {
+            return core::GameStatus::Player1Win;
+        }
+        if white > black {
+            return core::GameStatus::Player2Win;
+        }
+
+        return core::GameStatus::Draw;
+    }
+
+    fn state_hash(&self, state: &Self::State) -> Option<u64> {
+        // A pass leaves the discs as they are, only the player to move tells the positions
+        // apart
+        let hash = super::mix(state.boards[0]) ^ state.boards[1];
+        let hash = match state.player {
+            core::Player::Player1 => hash,
+            core::Player::Player2 => hash ^ PLAYER2_KEY,
+        };
+
+        // Mix the result so that similar positions don't collide in a table indexed by the
+        // low bits
+        return Some(super::mix(hash));
+    }
+}
+
+pub struct OthelloParser {}
+
+impl core::ActionParser for OthelloParser {
+    type Game = Othello;
+
+    fn read_action(&self) -> <Othello as core::Game>::Action {
+        loop {
+            println!("Enter [file][rank] or pass [e.g. d3]");
+
+            let mut move_str = String::new();
+            io::stdin()
+                .read_line(&mut move_str)
+                .expect("Failed to read line");
+
+            if let Some(action) = action_from_string(&move_str) {
+                return action;
+            }
+        }
+    }
+}
+
+// Score for a finished game from player's point of view, None if the game is still going
+fn terminal_score(game: &Othello, state: &OthelloState, player: core::Player) -> Option<f64> {
+    return match (game.status(state), player) {
+        (core::GameStatus::Player1Win, core::Player::Player1) => Some(f64::INFINITY),
+        (core::GameStatus::Player2Win, core::Player::Player2) => Some(f64::INFINITY),
+        (core::GameStatus::Player2Win, core::Player::Player1) => Some(f64::NEG_INFINITY),
+        (core::GameStatus::Player1Win, core::Player::Player2) => Some(f64::NEG_INFINITY),
+        (core::GameStatus::Draw, _) => Some(0.0),
+        _ => None,
+    };
+}
+
+// Worth of one disc, one legal move and one corner over the opponent
+const DISC_SCORE: f64 = 1.0;
+const MOBILITY_SCORE: f64 = 5.0;
+const CORNER_SCORE: f64 = 25.0;
+
+pub struct OthelloHeuristic {}
+
+impl strategy::Heuristic<Othello> for OthelloHeuristic {
+    fn name(&self) -> String {
+        return "discs-mobility-corners".to_string();
+    }
+
+    fn evaluate(&self, game: &Othello, state: &OthelloState, player: core::Player) -> f64 {
+        if let Some(score) = terminal_score(game, state, player) {
+            return score;
+        }
+
+        let opponent = core::other_player(player);
+        let ours = state.discs(player);
+        let theirs = state.discs(opponent);
+
+        // Discs change hands until the very end, so having more of them matters less than
+        // having more moves to choose from and corners, which can never be flipped
+        let discs = ours.count_ones() as f64 - theirs.count_ones() as f64;
+        let mobility =
+            state.moves(player).count_ones() as f64 - state.moves(opponent).count_ones() as f64;
+        let corners = (ours & CORNERS).count_ones() as f64 - (theirs & CORNERS).count_ones() as f64;
+
+        return DISC_SCORE * discs + MOBILITY_SCORE * mobility + CORNER_SCORE * corners;
+    }
+}
+
+// Corners first, the squares diagonally next to them last
+pub struct OthelloOrdering {}
+
+impl strategy::ActionOrdering<Othello> for OthelloOrdering {
+    fn priority(&self, _game: &Othello, _state: &OthelloState, action: &OthelloAction) -> i32 {
+        return match action {
+            OthelloAction::Place(square) if (1 << square) & CORNERS != 0 => 1,
+            OthelloAction::Place(square) if (1 << square) & X_SQUARES != 0 => -1,
+            _ => 0,
+        };
+    }
+}
+
+fn heuristics() -> Vec<Box<dyn strategy::Heuristic<Othello>>> {
+    return vec![Box::new(OthelloHeuristic {})];
+}
+
+impl playground::PlaygroundUtils for Othello {
+    fn strategies(&self) -> Vec<Box<dyn core::Strategy<Self>>> {
+        return vec![
+            Box::new(strategy::HumanStrategy {
+                parser: OthelloParser {},
+            }),
+            Box::new(strategy::RandomStrategy::new()),
+            Box::new(
+                strategy::MinMaxStrategy::new(heuristics(), 3, false)
+                    .with_ordering(Box::new(OthelloOrdering {})),
+            ),
+            Box::new(
+                strategy::MinMaxStrategy::new(heuristics(), 5, true)
+                    .with_ordering(Box::new(OthelloOrdering {})),
+            ),
+            Box::new(strategy::MCTSStrategy::new()),
+        ];
+    }
+
+    fn serialize_state(&self, state: &OthelloState) -> String {
+        let moves = state.moves(state.player);
+        let mut rows = vec!["  a b c d e f g h".to_string()];
+
+        for rank in 0..8 {
+            let cells: Vec<&str> = (0..8)
+                .map(|file| {
+                    let square = 1 << (rank * 8 + file);
+                    if state.boards[0] & square != 0 {
+                        return "X";
+                    }
+                    if state.boards[1] & square != 0 {
+                        return "O";
+                    }
+                    if moves & square != 0 {
+                        return "*";
+                    }
+                    return ".";
+                })
+                .collect();
+            rows.push(format!("{} {}", rank + 1, cells.join(" ")));
+        }
+
+        return rows.join("\n");
+    }
+
+    // Positions are written as the 64 squares from a1 to h8, X for black, O for white and -
+    // for empty, followed by the player to move, e.g.
+    // "---------------------------OX------XO--------------------------- X"
+    fn parse_state(&self, s: &str) -> Option<OthelloState> {
+        let fields: Vec<&str> = s.split_whitespace().collect();
+        if fields.len() != 2 || fields[0].chars().count() != 64 {
+            return None;
+        }
+
+        let mut boards = [0, 0];
+        for (idx, c) in fields[0].chars().enumerate() {
+            match c {
+                'X' | 'x' => boards[0] |= 1 << idx,
+                'O' | 'o' => boards[1] |= 1 << idx,
+                '-' | '.' => {}
+                _ => return None,
+            }
+        }
+
+        let player = match fields[1] {
+            "X" | "x" => core::Player::Player1,
+            "O" | "o" => core::Player::Player2,
+            _ => return None,
+        };
+
+        return Some(OthelloState {
+            boards: boards,
+            player: player,
+        });
+    }
+
+    fn serialize_action(&self, _state: &OthelloState, action: &OthelloAction) -> String {
+        return match action {
+            OthelloAction::Place(square) => square_name(*square),
+            OthelloAction::Pass => "pass".to_string(),
+        };
+    }
+}
@@ -1,6 +1,7 @@
 pub mod benchmark;
 pub mod core;
 pub mod playground;
+pub mod stats;
 
 pub mod games;
 pub mod strategy;
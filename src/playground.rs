@@ -9,6 +9,36 @@ where
 {
     fn strategies(&self) -> Vec<Box<dyn core::Strategy<Self>>>;
     fn serialize_state(&self, state: &<Self as core::Game>::State) -> String;
+
+    // Reads a position in the game's standard notation, None if the game has none or s isn't
+    // a valid position
+    fn parse_state(&self, _s: &str) -> Option<<Self as core::Game>::State> {
+        return None;
+    }
+
+    // Notation of action, played from state
+    fn serialize_action(
+        &self,
+        state: &<Self as core::Game>::State,
+        action: &<Self as core::Game>::Action,
+    ) -> String;
+
+    // Why a finished game ended
+    fn termination(&self, _state: &<Self as core::Game>::State) -> String {
+        return "normal".to_string();
+    }
+
+    // The match in the game's standard record format, None if the game has none
+    fn export_match(
+        &self,
+        _result: &core::MatchResult,
+        _event: &str,
+        _round: u64,
+        _player1: &str,
+        _player2: &str,
+    ) -> Option<String> {
+        return None;
+    }
 }
 
 pub trait PlaygroundGame {
@@ -56,6 +86,12 @@ where
 
         println!("{}", result);
 
+        if let Some(record) =
+            self.export_match(&result, "Playground", 1, &p1_strat.name(), &p2_strat.name())
+        {
+            println!("{}", record);
+        }
+
         return result;
     }
 }
@@ -66,11 +102,22 @@ pub fn simulate<G: core::Game + PlaygroundUtils>(
     p2_strat: &dyn core::Strategy<G>,
     debug: bool,
 ) -> core::MatchResult {
-    let mut state = game.init();
+    return simulate_from(game, game.init(), p1_strat, p2_strat, debug);
+}
 
+pub fn simulate_from<G: core::Game + PlaygroundUtils>(
+    game: &G,
+    mut state: G::State,
+    p1_strat: &dyn core::Strategy<G>,
+    p2_strat: &dyn core::Strategy<G>,
+    debug: bool,
+) -> core::MatchResult {
     let mut num_moves = 0;
+    let mut moves = Vec::new();
     let mut player1_time = time::Duration::new(0, 0);
     let mut player2_time = time::Duration::new(0, 0);
+    let mut player1_search = core::SearchTotals::default();
+    let mut player2_search = core::SearchTotals::default();
 
     while game.status(&state) == core::GameStatus::InProgress {
         if debug {
@@ -78,34 +125,54 @@ pub fn simulate<G: core::Game + PlaygroundUtils>(
         }
 
         let start = time::Instant::now();
+        let player = game.player(&state);
 
-        let action = match game.player(&state) {
+        let (action, elapsed) = match player {
             core::Player::Player1 => {
                 if debug {
                     println!("Player 1's turn...");
                 }
                 let action = p1_strat.select_action(game, &state);
-                player1_time = player1_time + (start.elapsed());
-                action
+                let elapsed = start.elapsed();
+                player1_time = player1_time + elapsed;
+                if let Some(info) = p1_strat.search_info() {
+                    player1_search.add(&info, elapsed);
+                }
+                (action, elapsed)
             }
             core::Player::Player2 => {
                 if debug {
                     println!("Player 2's turn...");
                 }
                 let action = p2_strat.select_action(game, &state);
-                player2_time = player2_time + (start.elapsed());
+                let elapsed = start.elapsed();
+                player2_time = player2_time + elapsed;
+                if let Some(info) = p2_strat.search_info() {
+                    player2_search.add(&info, elapsed);
+                }
                 num_moves = num_moves + 1;
-                action
+                (action, elapsed)
             }
         };
 
+        moves.push(core::MoveRecord {
+            player: player,
+            action: game.serialize_action(&state, &action),
+            time: elapsed,
+        });
+
         state = game.play(&action, &state);
     }
 
     return core::MatchResult {
         status: game.status(&state),
+        termination: game.termination(&state),
+        opening: None,
         num_moves: num_moves,
+        moves: moves,
         player1_time: player1_time,
         player2_time: player2_time,
+        player1_search: player1_search,
+        player2_search: player2_search,
     };
 }
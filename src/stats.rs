@@ -0,0 +1,263 @@
+// Normal quantile of a two sided 95% confidence interval
+pub const Z_95: f64 = 1.959964;
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum Outcome {
+    Win,
+    Draw,
+    Loss,
+}
+
+// Games won, drawn and lost by one side of a match
+#[derive(Clone, Copy, Default)]
+pub struct Score {
+    pub wins: u64,
+    pub draws: u64,
+    pub losses: u64,
+}
+
+impl Score {
+    pub fn new() -> Score {
+        return Score::default();
+    }
+
+    pub fn add(&mut self, outcome: Outcome) {
+        match outcome {
+            Outcome::Win => self.wins += 1,
+            Outcome::Draw => self.draws += 1,
+            Outcome::Loss => self.losses += 1,
+        }
+    }
+
+    pub fn merge(&mut self, other: &Score) {
+        self.wins += other.wins;
+        self.draws += other.draws;
+        self.losses += other.losses;
+    }
+
+    pub fn games(&self) -> u64 {
+        return self.wins + self.draws + self.losses;
+    }
+
+    // Wins count one point, draws half a point
+    pub fn points(&self) -> f64 {
+        return self.wins as f64 + self.draws as f64 / 2.0;
+    }
+
+    // Fraction of the available points that were scored
+    pub fn ratio(&self) -> f64 {
+        if self.games() == 0 {
+            return 0.5;
+        }
+
+        return self.points() / self.games() as f64;
+    }
+
+    // Wilson score interval of ratio, with draws counting as half a success
+    pub fn wilson_interval(&self, z: f64) -> (f64, f64) {
+        let n = self.games() as f64;
+        if n == 0.0 {
+            return (0.0, 1.0);
+        }
+
+        let p = self.ratio();
+        let z2 = z * z;
+        let center = (p + z2 / (2.0 * n)) / (1.0 + z2 / n);
+        let margin = z / (1.0 + z2 / n) * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt();
+
+        // The bound on the side of a perfect score is exactly 0 or 1, rounding would leave it
+        // slightly off or print it as -0
+        let low = if p == 0.0 {
+            0.0
+        } else {
+            (center - margin).max(0.0)
+        };
+        let high = if p == 1.0 {
+            1.0
+        } else {
+            (center + margin).min(1.0)
+        };
+
+        return (low, high);
+    }
+
+    pub fn elo(&self) -> f64 {
+        return elo_from_ratio(self.ratio());
+    }
+
+    // Confidence interval of elo, the Wilson interval of ratio converted to elo. Only a
+    // perfect score leaves a side open: all wins have no upper bound, all losses no lower one.
+    pub fn elo_interval(&self, z: f64) -> (f64, f64) {
+        let (low, high) = self.wilson_interval(z);
+        return (elo_from_ratio(low), elo_from_ratio(high));
+    }
+
+    // Likelihood of superiority: probability that this side is the stronger one. Draws
+    // carry no information about it.
+    pub fn los(&self) -> f64 {
+        let decisive = (self.wins + self.losses) as f64;
+        if decisive == 0.0 {
+            return 0.5;
+        }
+
+        let diff = self.wins as f64 - self.losses as f64;
+        return 0.5 * (1.0 + erf(diff / (2.0 * decisive).sqrt()));
+    }
+}
+
+// Elo difference that makes ratio the expected score
+pub fn elo_from_ratio(ratio: f64) -> f64 {
+    if ratio <= 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if ratio >= 1.0 {
+        return f64::INFINITY;
+    }
+
+    return -400.0 * (1.0 / ratio - 1.0).log10();
+}
+
+// Abramowitz and Stegun 7.1.26, absolute error below 1.5e-7
+pub fn erf(x: f64) -> f64 {
+    let sign = if x < 0.0 { -1.0 } else { 1.0 };
+    let x = x.abs();
+
+    let t = 1.0 / (1.0 + 0.3275911 * x);
+    let poly = t
+        * (0.254829592
+            + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+
+    return sign * (1.0 - poly * (-x * x).exp());
+}
+
+// Expected score of a side that is elo points stronger
+pub fn ratio_from_elo(elo: f64) -> f64 {
+    return 1.0 / (1.0 + 10f64.powf(-elo / 400.0));
+}
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum Hypothesis {
+    // The elo difference is at most elo0
+    H0,
+    // The elo difference is at least elo1
+    H1,
+}
+
+// Sequential probability ratio test between two elo differences, with alpha the chance of
+// accepting H1 when H0 holds and beta the chance of accepting H0 when H1 holds
+#[derive(Clone, Copy)]
+pub struct Sprt {
+    pub elo0: f64,
+    pub elo1: f64,
+    pub alpha: f64,
+    pub beta: f64,
+}
+
+impl Sprt {
+    pub fn new(elo0: f64, elo1: f64, alpha: f64, beta: f64) -> Sprt {
+        return Sprt {
+            elo0: elo0,
+            elo1: elo1,
+            alpha: alpha,
+            beta: beta,
+        };
+    }
+
+    // H0 is accepted when the log likelihood ratio falls below the first bound, H1 when
+    // it rises above the second
+    pub fn bounds(&self) -> (f64, f64) {
+        return (
+            (self.beta / (1.0 - self.alpha)).ln(),
+            ((1.0 - self.beta) / self.alpha).ln(),
+        );
+    }
+
+    // Generalized SPRT log likelihood ratio for win/draw/loss results, approximating the
+    // per game score as normally distributed
+    pub fn llr(&self, score: &Score) -> f64 {
+        if score.games() == 0 {
+            return 0.0;
+        }
+
+        // Half a game of each result is added so that a run of identical results doesn't
+        // make the variance vanish and end the test after a couple of games
+        let wins = score.wins as f64 + 0.5;
+        let draws = score.draws as f64 + 0.5;
+        let losses = score.losses as f64 + 0.5;
+        let n = wins + draws + losses;
+
+        let mean = (wins + draws / 2.0) / n;
+        let variance =
+            (wins * (1.0 - mean).powi(2) + draws * (0.5 - mean).powi(2) + losses * mean.powi(2))
+                / n;
+
+        let s0 = ratio_from_elo(self.elo0);
+        let s1 = ratio_from_elo(self.elo1);
+
+        return score.games() as f64 * (s1 - s0) * (2.0 * mean - s0 - s1) / (2.0 * variance);
+    }
+
+    pub fn decide(&self, score: &Score) -> Option<Hypothesis> {
+        let (lower, upper) = self.bounds();
+        let llr = self.llr(score);
+
+        if llr <= lower {
+            return Some(Hypothesis::H0);
+        }
+        if llr >= upper {
+            return Some(Hypothesis::H1);
+        }
+
+        return None;
+    }
+}
+
+// Virtual draws added to every pairing, like the prior of BayesElo, so that a player who won
+// or lost all of its games still gets a finite rating
+const PRIOR_DRAWS: f64 = 2.0;
+
+// Bradley-Terry ratings on the elo scale, averaging zero, fitted to the scores of
+// (player, opponent, score of player) pairings by minorization-maximization
+pub fn elo_ratings(num_players: usize, pairings: &[(usize, usize, Score)]) -> Vec<f64> {
+    let mut points = vec![0.0; num_players];
+    let mut games = vec![vec![0.0; num_players]; num_players];
+
+    for (a, b, score) in pairings {
+        let n = score.games() as f64 + PRIOR_DRAWS;
+        let a_points = score.points() + PRIOR_DRAWS / 2.0;
+        points[*a] += a_points;
+        points[*b] += n - a_points;
+        games[*a][*b] += n;
+        games[*b][*a] += n;
+    }
+
+    let mut strength = vec![1.0; num_players];
+
+    for _ in 0..10000 {
+        let mut change: f64 = 0.0;
+
+        for i in 0..num_players {
+            let mut denominator = 0.0;
+            for j in 0..num_players {
+                if games[i][j] > 0.0 {
+                    denominator += games[i][j] / (strength[i] + strength[j]);
+                }
+            }
+
+            if denominator > 0.0 {
+                let updated = points[i] / denominator;
+                change = change.max((updated / strength[i]).ln().abs());
+                strength[i] = updated;
+            }
+        }
+
+        if change < 1e-10 {
+            break;
+        }
+    }
+
+    let ratings: Vec<f64> = strength.iter().map(|s| 400.0 * s.log10()).collect();
+    let mean = ratings.iter().sum::<f64>() / num_players.max(1) as f64;
+
+    return ratings.iter().map(|r| r - mean).collect();
+}
@@ -0,0 +1,58 @@
+use serde_json::Value;
+
+use std::time;
+
+// Helpers for reading optional keys out of a strategy's JSON configuration.
+// Missing keys yield None, keys with the wrong type are a configuration error.
+
+pub fn read_u64(conf: &Value, key: &str) -> Option<u64> {
+    return match &conf[key] {
+        Value::Null => None,
+        v => Some(
+            v.as_u64()
+                .unwrap_or_else(|| panic!("{} must be a positive integer", key)),
+        ),
+    };
+}
+
+pub fn read_f64(conf: &Value, key: &str) -> Option<f64> {
+    return match &conf[key] {
+        Value::Null => None,
+        v => Some(
+            v.as_f64()
+                .unwrap_or_else(|| panic!("{} must be a number", key)),
+        ),
+    };
+}
+
+pub fn read_bool(conf: &Value, key: &str) -> Option<bool> {
+    return match &conf[key] {
+        Value::Null => None,
+        v => Some(
+            v.as_bool()
+                .unwrap_or_else(|| panic!("{} must be a boolean", key)),
+        ),
+    };
+}
+
+pub fn read_str<'a>(conf: &'a Value, key: &str) -> Option<&'a str> {
+    return match &conf[key] {
+        Value::Null => None,
+        v => Some(
+            v.as_str()
+                .unwrap_or_else(|| panic!("{} must be a string", key)),
+        ),
+    };
+}
+
+// Per-move time limit from timeLimitMs, or from time_limit_ms as older configs spell it
+pub fn read_time_limit(conf: &Value) -> Option<time::Duration> {
+    let ms = read_u64(conf, "timeLimitMs");
+    let alias = read_u64(conf, "time_limit_ms");
+    assert!(
+        ms.is_none() || alias.is_none(),
+        "timeLimitMs and time_limit_ms are the same setting, give only one"
+    );
+
+    return ms.or(alias).map(time::Duration::from_millis);
+}
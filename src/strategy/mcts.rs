@@ -1,10 +1,41 @@
 use crate::core;
 
-use rand::Rng;
+use super::config;
 
-use indextree::{Arena, Node};
+use rand::rngs::SmallRng;
+use rand::{FromEntropy, Rng, SeedableRng};
 
-pub struct MCTSStrategy {}
+use indextree::{Arena, Node, NodeId};
+
+use serde_json::Value;
+
+use std::cell::{Cell, RefCell};
+use std::thread;
+use std::time;
+
+pub struct MCTSStrategy<G: core::Game> {
+    // Search stops as soon as any configured budget is exhausted
+    iterations: Cell<Option<u64>>,
+    time_limit: Cell<Option<time::Duration>>,
+    exploration: Cell<f64>,
+    // Root parallelism: every thread grows its own tree
+    threads: Cell<usize>,
+    rng: RefCell<SmallRng>,
+    info: RefCell<Option<core::SearchInfo<G::Action>>>,
+}
+
+impl<G: core::Game> MCTSStrategy<G> {
+    pub fn new() -> MCTSStrategy<G> {
+        return MCTSStrategy {
+            iterations: Cell::new(Some(100)),
+            time_limit: Cell::new(None),
+            exploration: Cell::new(2.0),
+            threads: Cell::new(1),
+            rng: RefCell::new(SmallRng::from_entropy()),
+            info: RefCell::new(None),
+        };
+    }
+}
 
 struct NodeData<G: core::Game> {
     action: Option<G::Action>,
@@ -13,155 +44,331 @@ struct NodeData<G: core::Game> {
     wins: f64,
 }
 
-fn playout<G: core::Game>(node: &mut Node<NodeData<G>>, game: &G) -> core::GameStatus {
+fn playout<G: core::Game, R: Rng>(
+    node: &mut Node<NodeData<G>>,
+    game: &G,
+    rng: &mut R,
+) -> core::GameStatus {
     let data = node.get_mut();
     let mut state = data.state.clone();
 
     while game.status(&state) == core::GameStatus::InProgress {
         let actions = game.actions(&state);
-        let action_idx = rand::thread_rng().gen_range(0, actions.len());
+        let action_idx = rng.gen_range(0, actions.len());
         state = game.play(&actions[action_idx], &state);
     }
 
     return game.status(&state);
 }
 
-impl<G> core::Strategy<G> for MCTSStrategy
+// Follows the most visited children down from node
+fn principal_variation<G: core::Game>(tree: &Arena<NodeData<G>>, node: NodeId) -> Vec<G::Action> {
+    let mut pv = Vec::new();
+    let mut current = node;
+
+    loop {
+        pv.push(tree.get(current).unwrap().get().action.clone().unwrap());
+
+        let next = current.children(tree).max_by(|a, b| {
+            let na = tree.get(*a).unwrap().get();
+            let nb = tree.get(*b).unwrap().get();
+            return na.playouts.partial_cmp(&nb.playouts).unwrap();
+        });
+
+        match next {
+            Some(n) => current = n,
+            None => return pv,
+        }
+    }
+}
+
+// Root statistics of one search tree
+struct TreeResult<A> {
+    // (action, playouts) for every expanded root child
+    children: Vec<(A, f64)>,
+    pv: Vec<A>,
+    nodes: u64,
+    iterations: u64,
+}
+
+fn search_tree<G: core::Game, R: Rng>(
+    game: &G,
+    state: &G::State,
+    iterations: Option<u64>,
+    deadline: Option<time::Instant>,
+    exploration: f64,
+    rng: &mut R,
+) -> TreeResult<G::Action> {
+    let mut tree = Arena::new();
+    let root = tree.new_node(NodeData::<G> {
+        action: None,
+        state: (*state).clone(),
+        playouts: 0.0,
+        wins: 0.0,
+    });
+
+    let mut iteration = 0;
+
+    loop {
+        let mut current = root;
+
+        // Select a leaf for expansion
+        loop {
+            // Reached a leaf/terminal state
+            let cur_state = tree.get(current).unwrap().get().state.clone();
+
+            if game.status(&cur_state) != core::GameStatus::InProgress {
+                break;
+            }
+
+            // This node has un-expanded moves
+            let actions = game.actions(&cur_state);
+
+            if current.children(&tree).count() < actions.len() {
+                let expanded_actions = current
+                    .children(&tree)
+                    .map(|id| tree.get(id).unwrap().get().action.clone().unwrap())
+                    .collect::<Vec<G::Action>>();
+                // Find an action that hasn't been expanded
+                let first_unused = actions
+                    .iter()
+                    .filter(|x| !expanded_actions.contains(x))
+                    .nth(0)
+                    .unwrap();
+
+                let new = tree.new_node(NodeData::<G> {
+                    action: Some(first_unused.clone()),
+                    state: game.play(&first_unused, &cur_state),
+                    playouts: 0.0,
+                    wins: 0.0,
+                });
+
+                current.append(new, &mut tree);
+                current = new;
+                break;
+            }
+
+            // Select a node
+            current = current
+                .children(&tree)
+                .map(|c| {
+                    let node = tree.get(c).unwrap();
+                    let data = node.get();
+                    let parent = tree.get(node.parent().unwrap()).unwrap();
+                    let parent_data = parent.get();
+                    let score = (data.wins / (data.playouts + 1.0))
+                        + (exploration * (parent_data.playouts + 1.0).ln() / (data.playouts + 1.0))
+                            .sqrt();
+                    return (c, score);
+                })
+                .max_by(|a, b| {
+                    let (_, a_score) = a;
+                    let (_, b_score) = b;
+                    return a_score.partial_cmp(b_score).unwrap();
+                })
+                .map(|a| {
+                    let (n, _) = a;
+                    return n;
+                })
+                .unwrap();
+        }
+
+        let playout_id = current;
+        let playout_node = tree.get(playout_id).unwrap().get();
+
+        // Playout the node
+        let this_status = game.status(&playout_node.state);
+        let result = if this_status == core::GameStatus::InProgress {
+            playout(tree.get_mut(playout_id).unwrap(), game, rng)
+        } else {
+            this_status
+        };
+
+        // Propagate results up the tree
+        let mut cur_node = Some(playout_id);
+
+        while let Some(node_id) = cur_node {
+            let node = tree.get_mut(node_id).unwrap();
+            let data = node.get_mut();
+            data.playouts += 1.0;
+
+            match (&result, game.player(&data.state)) {
+                (core::GameStatus::Draw, _) => data.wins += 0.5,
+                (core::GameStatus::Player1Win, core::Player::Player2) => data.wins += 1.0,
+                (core::GameStatus::Player2Win, core::Player::Player1) => data.wins += 1.0,
+                _ => (),
+            }
+
+            cur_node = node.parent();
+        }
+
+        // Always run at least one iteration so that there is a move to return
+        iteration += 1;
+
+        if iterations.is_some_and(|n| iteration >= n)
+            || deadline.is_some_and(|d| time::Instant::now() >= d)
+        {
+            break;
+        }
+    }
+
+    // Choose the action with the best win rate for us
+    let best_node = root
+        .children(&tree)
+        .max_by(|a, b| {
+            let na = tree.get(*a).unwrap().get();
+            let nb = tree.get(*b).unwrap().get();
+            /*
+            return (na.wins / na.playouts)
+                .partial_cmp(&(nb.wins / nb.playouts))
+                .unwrap();*/
+            return na.playouts.partial_cmp(&nb.playouts).unwrap();
+        })
+        .unwrap();
+
+    let children = root
+        .children(&tree)
+        .map(|c| {
+            let data = tree.get(c).unwrap().get();
+            return (data.action.clone().unwrap(), data.playouts);
+        })
+        .collect();
+
+    return TreeResult {
+        children: children,
+        pv: principal_variation(&tree, best_node),
+        nodes: tree.len() as u64,
+        iterations: iteration,
+    };
+}
+
+impl<G> core::Strategy<G> for MCTSStrategy<G>
 where
-    G: core::Game,
+    G: core::Game + Sync,
 {
     fn name(&self) -> String {
         return "MCTS".to_string();
     }
 
+    fn configure(&self, conf: &Value) {
+        let iterations = config::read_u64(conf, "iterations");
+        let time_limit = config::read_time_limit(conf);
+
+        // Specifying either budget replaces the default one
+        if iterations.is_some() || time_limit.is_some() {
+            self.iterations.set(iterations);
+            self.time_limit.set(time_limit);
+        }
+
+        if let Some(exploration) = config::read_f64(conf, "exploration") {
+            self.exploration.set(exploration);
+        }
+
+        if let Some(threads) = config::read_u64(conf, "threads") {
+            assert!(threads > 0, "threads must be at least 1");
+            self.threads.set(threads as usize);
+        }
+
+        if let Some(seed) = config::read_u64(conf, "seed") {
+            *self.rng.borrow_mut() = SmallRng::seed_from_u64(seed);
+        }
+    }
+
+    fn seed(&self, seed: u64) {
+        *self.rng.borrow_mut() = SmallRng::seed_from_u64(seed);
+    }
+
+    fn search_info(&self) -> Option<core::SearchInfo<G::Action>> {
+        return self.info.borrow().clone();
+    }
+
     fn select_action(
         &self,
         game: &G,
         state: &<G as core::Game>::State,
     ) -> <G as core::Game>::Action {
-        let mut tree = Arena::new();
-        let root = tree.new_node(NodeData::<G> {
-            action: None,
-            state: (*state).clone(),
-            playouts: 0.0,
-            wins: 0.0,
-        });
+        let threads = self.threads.get();
+        let exploration = self.exploration.get();
+        let deadline = self.time_limit.get().map(|t| time::Instant::now() + t);
+        // The iteration budget is shared between the trees, the time budget applies to each
+        let iterations = self.iterations.get().map(|n| n.div_ceil(threads as u64));
 
-        for _ in 0..100 {
-            let mut current = root;
+        let mut rng = self.rng.borrow_mut();
 
-            // Select a leaf for expansion
-            loop {
-                // Reached a leaf/terminal state
-                let cur_state = tree.get(current).unwrap().get().state.clone();
+        let trees = if threads == 1 {
+            vec![search_tree(
+                game,
+                state,
+                iterations,
+                deadline,
+                exploration,
+                &mut *rng,
+            )]
+        } else {
+            // Each thread gets its own generator, seeded from ours
+            let seeds: Vec<u64> = (0..threads).map(|_| rng.gen()).collect();
 
-                if game.status(&cur_state) != core::GameStatus::InProgress {
-                    break;
-                }
+            thread::scope(|scope| {
+                let handles: Vec<_> = seeds
+                    .into_iter()
+                    .map(|seed| {
+                        scope.spawn(move || {
+                            let mut thread_rng = SmallRng::seed_from_u64(seed);
+                            return search_tree(
+                                game,
+                                state,
+                                iterations,
+                                deadline,
+                                exploration,
+                                &mut thread_rng,
+                            );
+                        })
+                    })
+                    .collect();
 
-                // This node has un-expanded moves
-                let actions = game.actions(&cur_state);
-
-                if current.children(&tree).count() < actions.len() {
-                    let expanded_actions = current
-                        .children(&tree)
-                        .map(|id| tree.get(id).unwrap().get().action.clone().unwrap())
-                        .collect::<Vec<G::Action>>();
-                    // Find an action that hasn't been expanded
-                    let first_unused = actions
-                        .iter()
-                        .filter(|x| !expanded_actions.contains(x))
-                        .nth(0)
-                        .unwrap();
-
-                    let new = tree.new_node(NodeData::<G> {
-                        action: Some(first_unused.clone()),
-                        state: game.play(&first_unused, &cur_state),
-                        playouts: 0.0,
-                        wins: 0.0,
-                    });
-
-                    current.append(new, &mut tree);
-                    current = new;
-                    break;
-                }
+                return handles
+                    .into_iter()
+                    .map(|h| h.join().expect("MCTS search thread panicked"))
+                    .collect::<Vec<_>>();
+            })
+        };
 
-                // Select a node
-                current = current
-                    .children(&tree)
-                    .map(|c| {
-                        let node = tree.get(c).unwrap();
-                        let data = node.get();
-                        let parent = tree.get(node.parent().unwrap()).unwrap();
-                        let parent_data = parent.get();
-                        let score = (data.wins / (data.playouts + 1.0))
-                            + (2.0 * (parent_data.playouts + 1.0).ln() / (data.playouts + 1.0))
-                                .sqrt();
-                        return (c, score);
-                    })
-                    .max_by(|a, b| {
-                        let (_, a_score) = a;
-                        let (_, b_score) = b;
-                        return a_score.partial_cmp(b_score).unwrap();
-                    })
-                    .map(|a| {
-                        let (n, _) = a;
-                        return n;
-                    })
-                    .unwrap();
-            }
+        // Merge the root children of every tree and play the most visited action overall
+        let mut merged: Vec<(G::Action, f64)> = Vec::new();
+        let mut info = core::SearchInfo::new();
 
-            let playout_id = current;
-            let playout_node = tree.get(playout_id).unwrap().get();
-
-            // Playout the node
-            let this_status = game.status(&playout_node.state);
-            let result = if this_status == core::GameStatus::InProgress {
-                playout(tree.get_mut(playout_id).unwrap(), game)
-            } else {
-                this_status
-            };
-
-            // Propagate results up the tree
-            let mut cur_node = Some(playout_id);
-
-            while let Some(node_id) = cur_node {
-                let node = tree.get_mut(node_id).unwrap();
-                let data = node.get_mut();
-                data.playouts += 1.0;
-
-                match (&result, game.player(&data.state)) {
-                    (core::GameStatus::Draw, _) => data.wins += 0.5,
-                    (core::GameStatus::Player1Win, core::Player::Player2) => data.wins += 1.0,
-                    (core::GameStatus::Player2Win, core::Player::Player1) => data.wins += 1.0,
-                    _ => (),
-                }
+        for tree in trees.iter() {
+            info.nodes += tree.nodes;
+            info.playouts += tree.iterations;
 
-                cur_node = node.parent();
+            for (action, playouts) in tree.children.iter() {
+                match merged.iter_mut().find(|(a, _)| a == action) {
+                    Some((_, total)) => *total += playouts,
+                    None => merged.push((action.clone(), *playouts)),
+                }
             }
         }
 
-        // Choose the action with the best win rate for us
-        let best_node = root
-            .children(&tree)
+        let (best_action, _) = merged
+            .into_iter()
+            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+            .unwrap();
+
+        // Report the line of the tree that visited the chosen action the most
+        info.pv = trees
+            .iter()
+            .filter(|t| t.pv.first() == Some(&best_action))
             .max_by(|a, b| {
-                let na = tree.get(*a).unwrap().get();
-                let nb = tree.get(*b).unwrap().get();
-                /*
-                return (na.wins / na.playouts)
-                    .partial_cmp(&(nb.wins / nb.playouts))
-                    .unwrap();*/
-                return na.playouts.partial_cmp(&nb.playouts).unwrap();
+                let visits = |t: &TreeResult<G::Action>| {
+                    return t.children.iter().find(|c| c.0 == best_action).unwrap().1;
+                };
+                return visits(a).partial_cmp(&visits(b)).unwrap();
             })
-            .unwrap();
+            .map(|t| t.pv.clone())
+            .unwrap_or_else(|| vec![best_action.clone()]);
+        info.depth = info.pv.len() as u32;
+        *self.info.borrow_mut() = Some(info);
 
-        return tree
-            .get(best_node)
-            .unwrap()
-            .get()
-            .action
-            .clone()
-            .unwrap()
-            .clone();
+        return best_action;
     }
 }
@@ -1,131 +1,646 @@
 use crate::core;
 use crate::core::*;
 
+use super::config;
+use super::transposition::{Bound, TTEntry, TranspositionTable};
+
+use serde_json::Value;
+
+use std::cell::{Cell, RefCell};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::atomic::{self, AtomicBool};
+use std::sync::Arc;
+use std::time;
+
 pub trait Heuristic<G: Game> {
+    fn name(&self) -> String;
     fn evaluate(&self, game: &G, state: &G::State, player: Player) -> f64;
 }
 
+// Cheap static guess at how good an action is. Actions with a priority of at least
+// STRONG_PRIORITY, like good captures, are searched first, highest first. Lower priorities are
+// only hints: the search's own killer move and history tables order those actions and the
+// hints break the remaining ties. Actions the game has no opinion about should get 0.
+pub trait ActionOrdering<G: Game> {
+    fn priority(&self, game: &G, state: &G::State, action: &G::Action) -> i32;
+}
+
+pub const STRONG_PRIORITY: i32 = 8;
+
 pub struct MinMaxStrategy<G: core::Game> {
-    pub heuristic: Box<dyn Heuristic<G>>,
-    pub search_depth: u8,
-    pub alpha_beta: bool,
+    heuristics: Vec<Box<dyn Heuristic<G>>>,
+    heuristic: Cell<usize>,
+    ordering: Option<Box<dyn ActionOrdering<G>>>,
+    use_ordering: Cell<bool>,
+    search_depth: Cell<u8>,
+    // Keep searching tactical actions past search_depth until the position is quiet
+    quiescence: Cell<bool>,
+    alpha_beta: Cell<bool>,
+    // Enables iterative deepening until the deadline, search_depth is then ignored
+    time_limit: Cell<Option<time::Duration>>,
+    // Optional cap on how deep iterative deepening goes under a time limit
+    max_depth: Cell<Option<u8>>,
+    // Set from another thread to end the search early, see Strategy::set_stop_flag
+    stop: RefCell<Option<Arc<AtomicBool>>>,
+    // Kept between moves, later searches reuse what earlier ones found
+    table: RefCell<TranspositionTable<G::Action>>,
+    info: RefCell<Option<SearchInfo<G::Action>>>,
 }
 
-#[derive(Clone)]
-struct AlphaBeta {
-    alpha: f64,
-    beta: f64,
+const DEFAULT_TT_SIZE: usize = 1 << 16;
+
+// Scores are relative to the searching player, so their positions are keyed apart
+const PLAYER2_KEY: u64 = 0x9e37_79b9_7f4a_7c15;
+
+impl<G: core::Game> MinMaxStrategy<G> {
+    // The first heuristic is used unless another one is selected by name through configure
+    pub fn new(
+        heuristics: Vec<Box<dyn Heuristic<G>>>,
+        search_depth: u8,
+        alpha_beta: bool,
+    ) -> MinMaxStrategy<G> {
+        assert!(
+            !heuristics.is_empty(),
+            "MinMax needs at least one heuristic"
+        );
+
+        return MinMaxStrategy {
+            heuristics: heuristics,
+            heuristic: Cell::new(0),
+            ordering: None,
+            use_ordering: Cell::new(true),
+            search_depth: Cell::new(search_depth),
+            quiescence: Cell::new(false),
+            alpha_beta: Cell::new(alpha_beta),
+            time_limit: Cell::new(None),
+            max_depth: Cell::new(None),
+            stop: RefCell::new(None),
+            table: RefCell::new(TranspositionTable::new(DEFAULT_TT_SIZE)),
+            info: RefCell::new(None),
+        };
+    }
+
+    pub fn with_ordering(mut self, ordering: Box<dyn ActionOrdering<G>>) -> MinMaxStrategy<G> {
+        self.ordering = Some(ordering);
+        return self;
+    }
 }
 
-fn evaluate<G: core::Game>(
-    game: &G,
-    state: &G::State,
-    depth: u8,
-    heuristic: &Box<dyn Heuristic<G>>,
-    alpha_beta_in: Option<AlphaBeta>,
+// How many nodes are searched between two looks at the clock, must be a power of two
+const CLOCK_CHECK_INTERVAL: u64 = 1024;
+
+// Negamax search: every score is from the point of view of the player to move in the position
+// it belongs to. Alpha-beta is fail-soft, a score outside of the (alpha, beta) window is still
+// a valid bound on the real score. Without alpha-beta every node is searched with the full
+// window, which makes the search a plain minimax.
+struct Search<'a, G: core::Game> {
+    game: &'a G,
+    heuristic: &'a dyn Heuristic<G>,
     max_player: Player,
-) -> f64 {
-    let player = game.player(&state);
-    let mut alpha_beta = alpha_beta_in.clone();
-
-    if depth == 0 || game.status(&state) != GameStatus::InProgress {
-        return heuristic.evaluate(game, state, max_player);
-    }
-
-    let actions = game.actions(&state);
-    let mut value;
-
-    if player == max_player {
-        value = -f64::INFINITY;
-        for action in actions {
-            value = value.max(evaluate(
-                game,
-                &game.play(&action, state),
-                depth - 1,
-                &heuristic,
-                alpha_beta.clone(),
-                max_player,
+    alpha_beta: bool,
+    quiescence: bool,
+    // None disables every kind of move ordering except trying the table's best move first
+    ordering: Option<Ordering<'a, G>>,
+    deadline: Option<time::Instant>,
+    stop: Option<&'a AtomicBool>,
+    table: &'a mut TranspositionTable<G::Action>,
+    nodes: u64,
+    aborted: bool,
+}
+
+struct Ordering<'a, G: core::Game> {
+    priorities: Option<&'a dyn ActionOrdering<G>>,
+    // Up to two quiet actions per ply that recently caused a cutoff
+    killers: Vec<[Option<G::Action>; 2]>,
+    // How much quiet actions caused cutoffs anywhere in the tree, weighted by depth
+    history: HashMap<G::Action, u64>,
+}
+
+impl<'a, G: core::Game> Ordering<'a, G>
+where
+    G::Action: Hash,
+{
+    fn priority(&self, game: &G, state: &G::State, action: &G::Action) -> i32 {
+        return match self.priorities {
+            Some(p) => p.priority(game, state, action),
+            None => 0,
+        };
+    }
+
+    fn killer_rank(&self, ply: usize, action: &G::Action) -> u8 {
+        return match self.killers.get(ply) {
+            Some([Some(k), _]) if k == action => 2,
+            Some([_, Some(k)]) if k == action => 1,
+            _ => 0,
+        };
+    }
+
+    fn sort(&self, game: &G, state: &G::State, actions: &mut [G::Action], ply: usize) {
+        actions.sort_by_cached_key(|a| {
+            let priority = self.priority(game, state, a);
+            let strong = if priority >= STRONG_PRIORITY {
+                priority
+            } else {
+                0
+            };
+
+            return Reverse((
+                strong,
+                self.killer_rank(ply, a),
+                self.history.get(a).cloned().unwrap_or(0),
+                priority,
             ));
+        });
+    }
+
+    fn record_cutoff(
+        &mut self,
+        game: &G,
+        state: &G::State,
+        action: &G::Action,
+        ply: usize,
+        depth: u8,
+    ) {
+        // Actions the game already ranks highly are found without help
+        if self.priority(game, state, action) >= STRONG_PRIORITY {
+            return;
+        }
+
+        if self.killers.len() <= ply {
+            self.killers.resize(ply + 1, [None, None]);
+        }
+
+        let killers = &mut self.killers[ply];
+        if killers[0].as_ref() != Some(action) {
+            killers[1] = killers[0].take();
+            killers[0] = Some(action.clone());
+        }
+
+        *self.history.entry(action.clone()).or_insert(0) += depth as u64 * depth as u64;
+    }
+}
+
+impl<'a, G: core::Game> Search<'a, G>
+where
+    G::Action: Hash,
+{
+    // Once the deadline has passed or the stop flag is set every pending evaluation unwinds
+    // without a result
+    fn should_abort(&mut self) -> bool {
+        if !self.aborted && self.nodes & (CLOCK_CHECK_INTERVAL - 1) == 0 {
+            if let Some(deadline) = self.deadline {
+                self.aborted = time::Instant::now() >= deadline;
+            }
+            if let Some(stop) = self.stop {
+                self.aborted |= stop.load(atomic::Ordering::Relaxed);
+            }
+        }
 
-            if let Some(ab) = &mut alpha_beta {
-                ab.alpha = ab.alpha.max(value);
+        return self.aborted;
+    }
+
+    fn key(&self, state: &G::State) -> Option<u64> {
+        let hash = self.game.state_hash(state)?;
+
+        return Some(match self.max_player {
+            Player::Player1 => hash,
+            Player::Player2 => hash ^ PLAYER2_KEY,
+        });
+    }
+
+    // Heuristic score of a leaf for the player to move in it. The heuristic is always asked
+    // from the searching player's point of view so scores match a plain minimax exactly.
+    fn leaf_score(&self, state: &G::State) -> f64 {
+        let score = self.heuristic.evaluate(self.game, state, self.max_player);
 
-                if ab.alpha >= ab.beta {
+        if self.game.player(state) == self.max_player {
+            return score;
+        }
+
+        return -score;
+    }
+
+    // Score of child for the player to move in its parent, usually that player's opponent
+    fn child_score(
+        &mut self,
+        player: Player,
+        child: &G::State,
+        depth: u8,
+        ply: usize,
+        alpha: f64,
+        beta: f64,
+    ) -> f64 {
+        if self.game.player(child) == player {
+            return self.negamax(child, depth, ply, alpha, beta);
+        }
+
+        return -self.negamax(child, depth, ply, -beta, -alpha);
+    }
+
+    fn negamax(
+        &mut self,
+        state: &G::State,
+        depth: u8,
+        ply: usize,
+        mut alpha: f64,
+        beta: f64,
+    ) -> f64 {
+        let game = self.game;
+
+        self.nodes += 1;
+
+        if self.should_abort() {
+            return 0.0;
+        }
+
+        if game.status(state) != GameStatus::InProgress {
+            return self.leaf_score(state);
+        }
+
+        if depth == 0 {
+            if self.quiescence {
+                return self.quiesce(state, ply, alpha, beta);
+            }
+
+            return self.leaf_score(state);
+        }
+
+        let player = game.player(state);
+        let key = self.key(state);
+        let mut actions = game.actions(state);
+
+        if let Some(ordering) = &self.ordering {
+            ordering.sort(game, state, &mut actions, ply);
+        }
+
+        if let Some(entry) = key.and_then(|k| self.table.probe(k)) {
+            if entry.depth >= depth {
+                let usable = match entry.bound {
+                    Bound::Exact => true,
+                    Bound::Lower => entry.value >= beta,
+                    Bound::Upper => entry.value <= alpha,
+                };
+
+                if usable {
+                    return entry.value;
+                }
+            }
+
+            // Otherwise search the move that was best last time first
+            if let Some(best) = &entry.best_action {
+                if let Some(idx) = actions.iter().position(|a| a == best) {
+                    let best = actions.remove(idx);
+                    actions.insert(0, best);
+                }
+            }
+        }
+
+        let alpha_in = alpha;
+        let mut value = -f64::INFINITY;
+        let mut best_idx = 0;
+
+        for (idx, action) in actions.iter().enumerate() {
+            let child = game.play(action, state);
+            let score = if self.alpha_beta {
+                self.child_score(player, &child, depth - 1, ply + 1, alpha, beta)
+            } else {
+                self.child_score(
+                    player,
+                    &child,
+                    depth - 1,
+                    ply + 1,
+                    -f64::INFINITY,
+                    f64::INFINITY,
+                )
+            };
+
+            if score > value {
+                value = score;
+                best_idx = idx;
+            }
+
+            if self.alpha_beta {
+                alpha = alpha.max(value);
+
+                if alpha >= beta {
+                    if let Some(ordering) = &mut self.ordering {
+                        ordering.record_cutoff(game, state, action, ply, depth);
+                    }
                     break;
                 }
             }
         }
-    } else {
-        value = f64::INFINITY;
-        for action in actions {
-            value = value.min(evaluate(
-                game,
-                &game.play(&action, state),
-                depth - 1,
-                &heuristic,
-                alpha_beta.clone(),
-                max_player,
+
+        if let (Some(key), false) = (key, self.aborted) {
+            let bound = if value <= alpha_in {
+                Bound::Upper
+            } else if value >= beta {
+                Bound::Lower
+            } else {
+                Bound::Exact
+            };
+
+            self.table.store(TTEntry::new(
+                key,
+                depth,
+                bound,
+                value,
+                Some(actions.swap_remove(best_idx)),
             ));
+        }
+
+        return value;
+    }
+
+    // Searches only tactical actions. The player to move may also stand pat and take the
+    // static score, unless every available action is tactical (e.g. when in check).
+    fn quiesce(&mut self, state: &G::State, ply: usize, mut alpha: f64, beta: f64) -> f64 {
+        let game = self.game;
+        let player = game.player(state);
+        let actions = game.actions(state);
+        let total = actions.len();
 
-            if let Some(ab) = &mut alpha_beta {
-                ab.beta = ab.beta.min(value);
+        let mut tactical: Vec<G::Action> = actions
+            .into_iter()
+            .filter(|a| game.is_tactical(state, a))
+            .collect();
 
-                if ab.beta <= ab.alpha {
+        let mut value = -f64::INFINITY;
+
+        if tactical.len() < total {
+            value = self.leaf_score(state);
+
+            if self.alpha_beta {
+                if value >= beta {
+                    return value;
+                }
+
+                alpha = alpha.max(value);
+            }
+        }
+
+        if let Some(ordering) = &self.ordering {
+            ordering.sort(game, state, &mut tactical, ply);
+        }
+
+        for action in tactical.iter() {
+            let child = game.play(action, state);
+            let score = if self.alpha_beta {
+                self.child_score(player, &child, 0, ply + 1, alpha, beta)
+            } else {
+                self.child_score(player, &child, 0, ply + 1, -f64::INFINITY, f64::INFINITY)
+            };
+
+            value = value.max(score);
+
+            if self.alpha_beta {
+                alpha = alpha.max(value);
+
+                if alpha >= beta {
                     break;
                 }
             }
         }
+
+        return value;
     }
 
-    return value;
+    // Follows the table's best actions from the chosen root action. Entries may have been
+    // overwritten by other positions, so the line stops at the first action that isn't legal.
+    fn principal_variation(
+        &self,
+        state: &G::State,
+        first: &G::Action,
+        max_len: usize,
+    ) -> Vec<G::Action> {
+        let game = self.game;
+        let mut pv = vec![first.clone()];
+        let mut current = game.play(first, state);
+
+        while pv.len() < max_len && game.status(&current) == GameStatus::InProgress {
+            let best = match self.key(&current).and_then(|k| self.table.get(k)) {
+                Some(entry) => entry.best_action.clone(),
+                None => None,
+            };
+
+            match best {
+                Some(action) if game.actions(&current).contains(&action) => {
+                    current = game.play(&action, &current);
+                    pv.push(action);
+                }
+                _ => break,
+            }
+        }
+
+        return pv;
+    }
+
+    // Finds the best root action at the given depth, None if the search ran out of time.
+    // The root window is shared between siblings, so once a good action is found the others
+    // only have to be proven no better than it. Ties go to the earliest action.
+    fn search_root(
+        &mut self,
+        state: &G::State,
+        actions: &[G::Action],
+        depth: u8,
+    ) -> Option<(usize, f64)> {
+        let player = self.game.player(state);
+        let mut best: Option<(usize, f64)> = None;
+        let mut alpha = -f64::INFINITY;
+
+        for (idx, action) in actions.iter().enumerate() {
+            let child = self.game.play(action, state);
+            let score = self.child_score(player, &child, depth, 1, alpha, f64::INFINITY);
+
+            if self.aborted {
+                return None;
+            }
+
+            if best.is_none_or(|(_, best_score)| score > best_score) {
+                best = Some((idx, score));
+            }
+
+            if self.alpha_beta {
+                alpha = alpha.max(score);
+            }
+        }
+
+        return best;
+    }
 }
 
 impl<G> core::Strategy<G> for MinMaxStrategy<G>
 where
     G: core::Game,
+    G::Action: Hash,
 {
     fn name(&self) -> String {
-        if self.alpha_beta {
+        if self.alpha_beta.get() {
             return "MinMaxAB".to_string();
         }
 
         return "MinMax".to_string();
     }
 
+    fn configure(&self, conf: &Value) {
+        if let Some(depth) = config::read_u64(conf, "depth") {
+            assert!(depth <= u8::MAX as u64, "depth must be at most {}", u8::MAX);
+            self.search_depth.set(depth as u8);
+        }
+
+        if let Some(depth) = config::read_u64(conf, "maxDepth") {
+            assert!(
+                depth <= u8::MAX as u64,
+                "maxDepth must be at most {}",
+                u8::MAX
+            );
+            self.max_depth.set(Some(depth as u8));
+        }
+
+        if let Some(alpha_beta) = config::read_bool(conf, "alphaBeta") {
+            self.alpha_beta.set(alpha_beta);
+        }
+
+        if let Some(name) = config::read_str(conf, "heuristic") {
+            let idx = self
+                .heuristics
+                .iter()
+                .position(|h| h.name() == name)
+                .unwrap_or_else(|| panic!("Unknown heuristic {}", name));
+
+            // Stored scores came from the previous heuristic
+            if idx != self.heuristic.get() {
+                self.table.borrow_mut().clear();
+            }
+
+            self.heuristic.set(idx);
+        }
+
+        if let Some(size) = config::read_u64(conf, "ttSize") {
+            self.table.borrow_mut().resize(size as usize);
+        }
+
+        if let Some(quiescence) = config::read_bool(conf, "quiescence") {
+            self.quiescence.set(quiescence);
+        }
+
+        if let Some(use_ordering) = config::read_bool(conf, "ordering") {
+            self.use_ordering.set(use_ordering);
+        }
+
+        if let Some(time_limit) = config::read_time_limit(conf) {
+            self.time_limit.set(Some(time_limit));
+        }
+    }
+
+    // Nothing is random, but the transposition table would carry results over from the
+    // previous game. Killer moves and history only live for a single search.
+    fn seed(&self, _seed: u64) {
+        self.table.borrow_mut().clear();
+    }
+
+    fn set_stop_flag(&self, stop: Arc<AtomicBool>) {
+        *self.stop.borrow_mut() = Some(stop);
+    }
+
+    fn search_info(&self) -> Option<SearchInfo<G::Action>> {
+        return self.info.borrow().clone();
+    }
+
     fn select_action(&self, game: &G, state: &G::State) -> G::Action {
-        let me = game.player(state);
+        let start = time::Instant::now();
+        let stop = self.stop.borrow().clone();
+        let mut table = self.table.borrow_mut();
+        let (probes, hits) = (table.probes(), table.hits());
 
-        // Map each action to its score
-        let actions = game.actions(state);
-        let scored = actions.iter().map(|a| {
-            (
-                a,
-                evaluate(
-                    game,
-                    &game.play(&a, state),
-                    self.search_depth,
-                    &self.heuristic,
-                    if self.alpha_beta {
-                        Some(AlphaBeta {
-                            alpha: -f64::INFINITY,
-                            beta: f64::INFINITY,
-                        })
-                    } else {
-                        None
-                    },
-                    me,
-                ),
-            )
-        });
+        table.new_search();
+
+        let mut search = Search {
+            game: game,
+            heuristic: self.heuristics[self.heuristic.get()].as_ref(),
+            max_player: game.player(state),
+            alpha_beta: self.alpha_beta.get(),
+            quiescence: self.quiescence.get(),
+            ordering: if self.use_ordering.get() {
+                Some(Ordering {
+                    priorities: self.ordering.as_ref().map(|o| o.as_ref()),
+                    killers: Vec::new(),
+                    history: HashMap::new(),
+                })
+            } else {
+                None
+            },
+            deadline: None,
+            stop: None,
+            table: &mut table,
+            nodes: 0,
+            aborted: false,
+        };
+
+        let mut actions = game.actions(state);
+        let max_depth = match self.time_limit.get() {
+            Some(_) => self.max_depth.get().unwrap_or(u8::MAX),
+            None => self.search_depth.get(),
+        };
+
+        // Only the static priorities are used at the root so that the order doesn't depend
+        // on whether earlier searches pruned anything
+        if let Some(ordering) = &self.ordering {
+            if self.use_ordering.get() {
+                actions.sort_by_cached_key(|a| Reverse(ordering.priority(game, state, a)));
+            }
+        }
+
+        // Without a time limit or a stop flag go straight to the full depth, otherwise deepen
+        // one ply at a time until the deadline or the stop and keep the best action of the
+        // last search that finished
+        let first_depth = if self.time_limit.get().is_some() || stop.is_some() {
+            0
+        } else {
+            max_depth
+        };
 
-        let (best_action, _) = scored
-            .max_by(|a, b| {
-                let (_, score_a) = a;
-                let (_, score_b) = b;
-                return score_a.partial_cmp(score_b).unwrap();
-            })
-            .unwrap();
+        let mut completed_depth = first_depth;
+
+        for depth in first_depth..=max_depth {
+            // The shallowest search always runs to completion so there is an action to return
+            if depth > first_depth {
+                search.deadline = self.time_limit.get().map(|t| start + t);
+                search.stop = stop.as_deref();
+            }
+
+            let (best_idx, best_score) = match search.search_root(state, &actions, depth) {
+                Some(best) => best,
+                None => break,
+            };
+
+            // Search the previous best action first on the next iteration
+            let best_action = actions.remove(best_idx);
+            actions.insert(0, best_action);
+            completed_depth = depth;
+
+            // A forced win or loss will not change with more depth
+            if best_score.is_infinite() {
+                break;
+            }
+        }
+
+        // Root children are searched at depth, so the root itself adds a ply
+        let plies = completed_depth as u32 + 1;
+        let pv = search.principal_variation(state, &actions[0], plies as usize);
+        let nodes = search.nodes;
+
+        *self.info.borrow_mut() = Some(SearchInfo {
+            nodes: nodes,
+            depth: plies,
+            playouts: 0,
+            tt_probes: table.probes() - probes,
+            tt_hits: table.hits() - hits,
+            pv: pv,
+        });
 
-        return best_action.clone();
+        return actions.swap_remove(0);
     }
 }
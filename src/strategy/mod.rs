@@ -12,3 +12,8 @@ pub use self::minmax::*;
 
 mod mcts;
 pub use self::mcts::*;
+
+mod config;
+
+mod transposition;
+pub use self::transposition::*;
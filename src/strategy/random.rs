@@ -1,8 +1,25 @@
 use crate::core;
 
-use rand::Rng;
+use super::config;
 
-pub struct RandomStrategy {}
+use rand::rngs::SmallRng;
+use rand::{FromEntropy, Rng, SeedableRng};
+
+use serde_json::Value;
+
+use std::cell::RefCell;
+
+pub struct RandomStrategy {
+    rng: RefCell<SmallRng>,
+}
+
+impl RandomStrategy {
+    pub fn new() -> RandomStrategy {
+        return RandomStrategy {
+            rng: RefCell::new(SmallRng::from_entropy()),
+        };
+    }
+}
 
 impl<G> core::Strategy<G> for RandomStrategy
 where
@@ -12,13 +29,23 @@ where
         return "Random".to_string();
     }
 
+    fn configure(&self, conf: &Value) {
+        if let Some(seed) = config::read_u64(conf, "seed") {
+            *self.rng.borrow_mut() = SmallRng::seed_from_u64(seed);
+        }
+    }
+
+    fn seed(&self, seed: u64) {
+        *self.rng.borrow_mut() = SmallRng::seed_from_u64(seed);
+    }
+
     fn select_action(
         &self,
         game: &G,
         state: &<G as core::Game>::State,
     ) -> <G as core::Game>::Action {
         let mut all_actions = game.actions(state);
-        let action_idx = rand::thread_rng().gen_range(0, all_actions.len());
+        let action_idx = self.rng.borrow_mut().gen_range(0, all_actions.len());
         return all_actions.remove(action_idx);
     }
 }
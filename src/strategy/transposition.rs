@@ -0,0 +1,154 @@
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum Bound {
+    // The stored value is the exact score of the position
+    Exact,
+    // The search failed high, the real score is at least the stored value
+    Lower,
+    // The search failed low, the real score is at most the stored value
+    Upper,
+}
+
+#[derive(Clone)]
+pub struct TTEntry<A> {
+    pub key: u64,
+    pub depth: u8,
+    pub bound: Bound,
+    pub value: f64,
+    pub best_action: Option<A>,
+    generation: u8,
+}
+
+impl<A> TTEntry<A> {
+    pub fn new(
+        key: u64,
+        depth: u8,
+        bound: Bound,
+        value: f64,
+        best_action: Option<A>,
+    ) -> TTEntry<A> {
+        return TTEntry {
+            key: key,
+            depth: depth,
+            bound: bound,
+            value: value,
+            best_action: best_action,
+            generation: 0,
+        };
+    }
+}
+
+// Fixed size hash table of search results. Each key maps to a single slot, an occupied slot
+// is only overwritten by the same position, by an entry left over from an earlier search or
+// by a search that went at least as deep.
+pub struct TranspositionTable<A> {
+    size: usize,
+    entries: Vec<Option<TTEntry<A>>>,
+    generation: u8,
+    probes: u64,
+    hits: u64,
+}
+
+impl<A: Clone> TranspositionTable<A> {
+    pub fn new(size: usize) -> TranspositionTable<A> {
+        // Entries are allocated on first use so that idle strategies stay cheap
+        return TranspositionTable {
+            size: size,
+            entries: Vec::new(),
+            generation: 0,
+            probes: 0,
+            hits: 0,
+        };
+    }
+
+    pub fn size(&self) -> usize {
+        return self.size;
+    }
+
+    pub fn resize(&mut self, size: usize) {
+        self.size = size;
+        self.clear();
+    }
+
+    pub fn clear(&mut self) {
+        self.entries = Vec::new();
+    }
+
+    // Marks the start of a new search, entries stored before it become replaceable
+    pub fn new_search(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    pub fn probe(&mut self, key: u64) -> Option<&TTEntry<A>> {
+        if self.size == 0 {
+            return None;
+        }
+
+        self.probes += 1;
+
+        let idx = self.index(key);
+        match self.entries.get(idx) {
+            Some(Some(entry)) if entry.key == key => {
+                self.hits += 1;
+                return Some(entry);
+            }
+            _ => return None,
+        }
+    }
+
+    // Looks up key without counting it as a probe
+    pub fn get(&self, key: u64) -> Option<&TTEntry<A>> {
+        if self.size == 0 {
+            return None;
+        }
+
+        return match self.entries.get(self.index(key)) {
+            Some(Some(entry)) if entry.key == key => Some(entry),
+            _ => None,
+        };
+    }
+
+    pub fn store(&mut self, mut entry: TTEntry<A>) {
+        if self.size == 0 {
+            return;
+        }
+
+        if self.entries.is_empty() {
+            self.entries = vec![None; self.size];
+        }
+
+        let idx = self.index(entry.key);
+        let replace = match &self.entries[idx] {
+            None => true,
+            Some(old) => {
+                old.key == entry.key
+                    || old.generation != self.generation
+                    || entry.depth >= old.depth
+            }
+        };
+
+        if replace {
+            entry.generation = self.generation;
+            self.entries[idx] = Some(entry);
+        }
+    }
+
+    pub fn probes(&self) -> u64 {
+        return self.probes;
+    }
+
+    pub fn hits(&self) -> u64 {
+        return self.hits;
+    }
+
+    pub fn hit_rate(&self) -> f64 {
+        if self.probes == 0 {
+            return 0.0;
+        }
+
+        return self.hits as f64 / self.probes as f64;
+    }
+
+    fn index(&self, key: u64) -> usize {
+        return (key % self.size as u64) as usize;
+    }
+}
@@ -1,25 +1,136 @@
 use crate::core;
+use crate::core::Game;
 use crate::games;
-use std::panic;
+
+use super::config;
+
+use serde_json::Value;
+
+use std::cell::{Cell, RefCell};
+use std::env;
+use std::path::Path;
 
 extern crate uci;
 
+#[derive(Clone, Copy)]
+pub enum SearchLimit {
+    MoveTime(u64),
+    Depth(u64),
+    Nodes(u64),
+}
+
+// Plays the moves of an external UCI engine. The engine is started on the first move after
+// the strategy is configured, so that listing the strategy doesn't need the engine installed.
 pub struct UCIStrategy {
-    engine: uci::Engine,
+    path: RefCell<String>,
+    // setoption name/value pairs sent after the engine starts
+    options: RefCell<Vec<(String, String)>>,
+    limit: Cell<SearchLimit>,
+    engine: RefCell<Option<uci::Engine>>,
+    info: RefCell<Option<core::SearchInfo<games::ChessAction>>>,
 }
 
 impl UCIStrategy {
-    pub fn new() -> Option<UCIStrategy> {
-        let e = panic::catch_unwind(|| {
-            return uci::Engine::new("stockfish").unwrap();
-        });
+    pub fn new() -> UCIStrategy {
+        return UCIStrategy {
+            path: RefCell::new("stockfish".to_string()),
+            options: RefCell::new(Vec::new()),
+            limit: Cell::new(SearchLimit::MoveTime(100)),
+            engine: RefCell::new(None),
+            info: RefCell::new(None),
+        };
+    }
+
+    fn start_engine(&self) -> uci::Engine {
+        let path = self.path.borrow();
+
+        // The uci crate panics with no hint of the path when the engine can't be spawned
+        if !executable_exists(&path) {
+            panic!("UCI engine {} not found", path);
+        }
+
+        let engine = uci::Engine::new(&path).expect("Failed to start UCI engine");
 
-        if let Ok(engine) = e {
-            return Some(UCIStrategy { engine: engine });
+        for (name, value) in self.options.borrow().iter() {
+            engine
+                .set_option(name, value)
+                .unwrap_or_else(|e| panic!("Failed to set UCI option {}: {:?}", name, e));
         }
 
-        return None;
+        return engine;
+    }
+}
+
+// Whether path names a file, directly or through the directories of PATH
+fn executable_exists(path: &str) -> bool {
+    if path.contains(std::path::MAIN_SEPARATOR) {
+        return Path::new(path).is_file();
+    }
+
+    return match env::var_os("PATH") {
+        Some(dirs) => env::split_paths(&dirs).any(|dir| dir.join(path).is_file()),
+        None => false,
+    };
+}
+
+// Reads a move sent by the engine, which has to be legal in state
+fn parse_move(
+    game: &games::Chess,
+    state: &games::ChessState,
+    s: &str,
+) -> Result<games::ChessAction, &'static str> {
+    let action = games::action_from_string(s).ok_or("Malformed move")?;
+
+    if !game.actions(state).contains(&action) {
+        return Err("Illegal move");
     }
+
+    return Ok(action);
+}
+
+// Builds search info from the last "info ... pv ..." line the engine printed
+fn parse_search_info(
+    game: &games::Chess,
+    state: &games::ChessState,
+    output: &str,
+) -> core::SearchInfo<games::ChessAction> {
+    let mut info = core::SearchInfo::new();
+
+    let line = match output
+        .lines()
+        .rev()
+        .find(|l| l.starts_with("info") && l.contains(" pv "))
+    {
+        Some(l) => l,
+        None => return info,
+    };
+
+    let mut tokens = line.split_whitespace();
+
+    while let Some(token) = tokens.next() {
+        match token {
+            "depth" => info.depth = tokens.next().and_then(|t| t.parse().ok()).unwrap_or(0),
+            "nodes" => info.nodes = tokens.next().and_then(|t| t.parse().ok()).unwrap_or(0),
+            // The principal variation runs until the end of the line, it is cut at the first
+            // move that isn't legal where it is played
+            "pv" => {
+                let mut pv_state = state.clone();
+
+                for token in tokens.by_ref() {
+                    let action = match parse_move(game, &pv_state, token) {
+                        Ok(action) => action,
+                        Err(_) => break,
+                    };
+
+                    pv_state = game.play(&action, &pv_state);
+                    info.pv.push(action);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    return info;
 }
 
 impl core::Strategy<games::Chess> for UCIStrategy {
@@ -27,13 +138,85 @@ impl core::Strategy<games::Chess> for UCIStrategy {
         return "UCI".to_string();
     }
 
-    fn select_action(&self, _game: &games::Chess, state: &games::ChessState) -> games::ChessAction {
+    fn configure(&self, conf: &Value) {
+        let mut restart = false;
+
+        if let Some(path) = config::read_str(conf, "path") {
+            *self.path.borrow_mut() = path.to_string();
+            restart = true;
+        }
+
+        match &conf["options"] {
+            Value::Null => {}
+            Value::Object(options) => {
+                *self.options.borrow_mut() = options
+                    .iter()
+                    .map(|(name, value)| {
+                        let value = match value {
+                            Value::String(s) => s.clone(),
+                            v => v.to_string(),
+                        };
+                        return (name.clone(), value);
+                    })
+                    .collect();
+                restart = true;
+            }
+            _ => panic!("options must be an object"),
+        }
+
+        let limits = [
+            config::read_u64(conf, "movetime").map(SearchLimit::MoveTime),
+            config::read_u64(conf, "depth").map(SearchLimit::Depth),
+            config::read_u64(conf, "nodes").map(SearchLimit::Nodes),
+        ];
+        let mut limits = limits.iter().flatten();
+
+        if let Some(limit) = limits.next() {
+            if limits.next().is_some() {
+                panic!("Only one of movetime, depth and nodes can be set");
+            }
+            self.limit.set(*limit);
+        }
+
+        if restart {
+            *self.engine.borrow_mut() = None;
+        }
+    }
+
+    fn search_info(&self) -> Option<core::SearchInfo<games::ChessAction>> {
+        return self.info.borrow().clone();
+    }
+
+    fn select_action(&self, game: &games::Chess, state: &games::ChessState) -> games::ChessAction {
+        let mut engine = self.engine.borrow_mut();
+        let engine = engine.get_or_insert_with(|| self.start_engine());
+
         let fen = format!("{}", state.board);
-        self.engine
-            .set_position(&fen)
-            .expect("Failed to set position");
+        engine.set_position(&fen).expect("Failed to set position");
+
+        let go = match self.limit.get() {
+            SearchLimit::MoveTime(ms) => format!("go movetime {}", ms),
+            SearchLimit::Depth(depth) => format!("go depth {}", depth),
+            SearchLimit::Nodes(nodes) => format!("go nodes {}", nodes),
+        };
+        let output = engine
+            .command_and_wait_for(&go, "bestmove")
+            .expect("Failed to get move");
+        *self.info.borrow_mut() = Some(parse_search_info(game, state, &output));
 
-        let bestmove = self.engine.bestmove().expect("Failed to get move");
-        return games::action_from_string(&bestmove).unwrap();
+        let bestmove = output
+            .lines()
+            .last()
+            .and_then(|l| l.split_whitespace().nth(1))
+            .ok_or("Missing move")
+            .and_then(|m| parse_move(game, state, m));
+
+        return bestmove.unwrap_or_else(|e| {
+            panic!(
+                "UCI engine {} sent a bad best move: {}",
+                self.path.borrow(),
+                e
+            )
+        });
     }
 }
@@ -0,0 +1,113 @@
+use board_game_ai::benchmark::{BenchmarkResult, Test};
+use board_game_ai::games::MnkGame;
+use board_game_ai::stats::{Outcome, Score, Sprt};
+
+use serde_json::{json, Value};
+
+fn sprt_test(threads: usize) -> BenchmarkResult {
+    let test = Test {
+        label: "SPRT".to_string(),
+        game: Box::new(MnkGame::tic_tac_toe()),
+        p1_strat_conf: json!({"name": "MCTS", "iterations": 20}),
+        p2_strat_conf: json!({"name": "Random"}),
+        iterations: 500,
+        threads: threads,
+        seed: Some(7),
+        swap_colors: true,
+        sprt: Some(Sprt::new(0.0, 50.0, 0.05, 0.05)),
+        export: None,
+        openings: Vec::new(),
+    };
+
+    return test.game.run(&test).expect("Failed to run test");
+}
+
+// Strategy 1's score of every game in the order they were scheduled
+fn game_scores(result: &BenchmarkResult) -> Vec<String> {
+    return result
+        .csv_rows()
+        .lines()
+        .map(|row| row.split(',').nth(9).unwrap().to_string())
+        .collect();
+}
+
+#[test]
+fn sprt_stops_at_the_first_decided_pair_whatever_the_thread_count() {
+    let sprt = Sprt::new(0.0, 50.0, 0.05, 0.05);
+    let single = sprt_test(1);
+    let scores = game_scores(&single);
+
+    assert!(scores.len() < 1000, "the SPRT never decided");
+
+    let mut score = Score::new();
+    for (i, s) in scores.iter().enumerate() {
+        score.add(match s.as_str() {
+            "1" => Outcome::Win,
+            "0.5" => Outcome::Draw,
+            _ => Outcome::Loss,
+        });
+
+        // Only complete pairs of games can end the match
+        let decided = i % 2 == 1 && sprt.decide(&score).is_some();
+        assert!(
+            decided == (i == scores.len() - 1),
+            "game {} of {}",
+            i,
+            scores.len()
+        );
+    }
+
+    for threads in [2, 4, 8].iter() {
+        let result = sprt_test(*threads);
+
+        assert!(game_scores(&result) == scores, "{} threads", threads);
+        assert!(result.to_json()["sprt"] == single.to_json()["sprt"]);
+        assert!(result.to_json()["matchCount"] == Value::from(500));
+        assert!(result.to_json()["gamesPlayed"] == Value::from(scores.len()));
+    }
+}
+
+fn random_test(p1_seed: Option<u64>) -> BenchmarkResult {
+    let mut p1_strat_conf = json!({"name": "Random"});
+    if let Some(seed) = p1_seed {
+        p1_strat_conf["seed"] = json!(seed);
+    }
+
+    let test = Test {
+        label: "Seeds".to_string(),
+        game: Box::new(MnkGame::tic_tac_toe()),
+        p1_strat_conf: p1_strat_conf,
+        p2_strat_conf: json!({"name": "Random"}),
+        iterations: 20,
+        threads: 2,
+        seed: Some(7),
+        swap_colors: false,
+        sprt: None,
+        export: None,
+        openings: Vec::new(),
+    };
+
+    return test.game.run(&test).expect("Failed to run test");
+}
+
+#[test]
+fn a_strategy_seed_changes_its_games_reproducibly() {
+    // Every action of every game, without the timings
+    let moves = |result: &BenchmarkResult| -> Vec<Value> {
+        return result.to_json()["games"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .flat_map(|g| g["moves"].as_array().unwrap().clone())
+            .map(|m| m["action"].clone())
+            .collect();
+    };
+
+    let unseeded = moves(&random_test(None));
+    let seeded = moves(&random_test(Some(1)));
+
+    assert!(moves(&random_test(Some(1))) == seeded);
+    assert!(moves(&random_test(None)) == unseeded);
+    assert!(seeded != unseeded, "the strategy seed was ignored");
+    assert!(moves(&random_test(Some(2))) != seeded);
+}
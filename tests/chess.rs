@@ -0,0 +1,81 @@
+use board_game_ai::games;
+
+use chess::{Board, ChessMove, Piece, Square};
+
+use std::str::FromStr;
+
+fn san(fen: &str, src: Square, dst: Square, promotion: Option<Piece>) -> String {
+    let board = Board::from_str(fen).expect("Invalid FEN");
+    let chess_move = ChessMove::new(src, dst, promotion);
+    assert!(board.legal(chess_move), "{} is illegal", chess_move);
+
+    return games::san(&board, chess_move);
+}
+
+#[test]
+fn san_disambiguates_by_file_then_rank() {
+    // Knights on b1 and f3 can both go to d2
+    let fen = "4k3/8/8/8/8/5N2/8/1N2K3 w - - 0 1";
+    assert_eq!(san(fen, Square::B1, Square::D2, None), "Nbd2");
+    assert_eq!(san(fen, Square::F3, Square::D2, None), "Nfd2");
+
+    // Rooks on a1 and a5 share a file, so the rank tells them apart
+    let fen = "4k3/8/8/R7/8/8/8/R3K3 w - - 0 1";
+    assert_eq!(san(fen, Square::A1, Square::A3, None), "R1a3");
+    assert_eq!(san(fen, Square::A5, Square::A3, None), "R5a3");
+
+    // Queens on a1, a3 and c1 all reach b2, a1 shares a file with a3 and a rank with c1
+    let fen = "4k3/8/8/8/8/Q7/8/Q1Q1K3 w - - 0 1";
+    assert_eq!(san(fen, Square::A1, Square::B2, None), "Qa1b2");
+    assert_eq!(san(fen, Square::C1, Square::B2, None), "Qcb2");
+}
+
+#[test]
+fn san_castles_on_both_sides() {
+    let fen = "r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1";
+    assert_eq!(san(fen, Square::E1, Square::G1, None), "O-O");
+    assert_eq!(san(fen, Square::E1, Square::C1, None), "O-O-O");
+
+    let fen = "r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1";
+    assert_eq!(san(fen, Square::E8, Square::G8, None), "O-O");
+    assert_eq!(san(fen, Square::E8, Square::C8, None), "O-O-O");
+}
+
+#[test]
+fn san_names_promotions() {
+    let fen = "3r4/4P3/8/8/8/3k4/8/4K3 w - - 0 1";
+    assert_eq!(san(fen, Square::E7, Square::E8, Some(Piece::Queen)), "e8=Q");
+    assert_eq!(
+        san(fen, Square::E7, Square::E8, Some(Piece::Knight)),
+        "e8=N"
+    );
+    assert_eq!(
+        san(fen, Square::E7, Square::D8, Some(Piece::Rook)),
+        "exd8=R+"
+    );
+}
+
+#[test]
+fn san_marks_check_and_mate() {
+    let fen = "4k3/8/8/8/8/8/8/R3K3 w - - 0 1";
+    assert_eq!(san(fen, Square::A1, Square::A8, None), "Ra8+");
+
+    let fen = "6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1";
+    assert_eq!(san(fen, Square::A1, Square::A8, None), "Ra8#");
+    assert_eq!(san(fen, Square::A1, Square::A7, None), "Ra7");
+}
+
+#[test]
+fn long_algebraic_promotions_are_lowercase() {
+    let promotion = |s: &str| games::action_from_string(s).map(|a| a.chess_move.get_promotion());
+
+    assert!(promotion("e7e8q") == Some(Some(Piece::Queen)));
+    assert!(promotion("e7e8r") == Some(Some(Piece::Rook)));
+    assert!(promotion("e7e8b") == Some(Some(Piece::Bishop)));
+    assert!(promotion("e7e8n") == Some(Some(Piece::Knight)));
+    assert!(promotion("e2e4") == Some(None));
+
+    assert!(promotion("e7e8Q").is_none());
+    assert!(promotion("e7e8k").is_none());
+    assert!(promotion("e7e8x").is_none());
+}
@@ -0,0 +1,117 @@
+use board_game_ai::core::{Game, GameStatus, Strategy};
+use board_game_ai::games::{ConnectFour, ConnectFourHeuristic, ConnectFourOrdering};
+use board_game_ai::playground::PlaygroundUtils;
+use board_game_ai::strategy::MinMaxStrategy;
+
+use serde_json::json;
+
+// Status after playing columns, numbered from 1, starting with player 1
+fn status(columns: &str) -> GameStatus {
+    let game = ConnectFour {};
+    let state = game.parse_state(columns).expect("Invalid moves");
+    return game.status(&state);
+}
+
+#[test]
+fn fours_in_every_direction_win() {
+    // Bottom row, against the right edge
+    // . . . O O O .
+    // . . . X X X X
+    assert!(status("4455667") == GameStatus::Player1Win);
+
+    // Top of the first column
+    // X . . . . . .
+    // X . . . . . .
+    // X . . . . . .
+    // X O . . . . .
+    // O O O . . . .
+    // O X X . . . .
+    assert!(status("21311213121") == GameStatus::Player1Win);
+
+    // Rising diagonal
+    // . . . X . . .
+    // . . X O . . .
+    // . X X O . . .
+    // X O O O X . .
+    assert!(status("12233434544") == GameStatus::Player1Win);
+
+    // Falling diagonal
+    // . . . X . . .
+    // . . . O X . .
+    // . . . O X X .
+    // . . X O O O X
+    assert!(status("76655454344") == GameStatus::Player1Win);
+
+    // Player 2 gets there first on the bottom row
+    // . . X X . . .
+    // . X X O . . .
+    // X O O O O . .
+    assert!(status("1223343445") == GameStatus::Player2Win);
+}
+
+// The bitboard keeps an empty cell above every column so that shifting a line past the top
+// of one column doesn't carry it into the bottom of the next. None of these are four in a row.
+#[test]
+fn lines_do_not_wrap_between_columns() {
+    // Top three of the first column and the bottom of the second
+    // X . . . . . .
+    // X . . . . . .
+    // X . . . . . .
+    // O . . . . . .
+    // X . . . O O O
+    // O X . . X O X
+    assert!(status("7127551116161") == GameStatus::InProgress);
+
+    // Rising diagonal that reaches the top, then the bottom of the next column
+    // . . X . . . .
+    // . X O . . . .
+    // X O O . . . .
+    // O X X . . . .
+    // X X O . O X O
+    // X O O X O X O
+    assert!(status("4227256762131321331335") == GameStatus::InProgress);
+
+    // Falling diagonal that reaches the bottom, then the top of the next column
+    // . . . X . . .
+    // . . . O . . .
+    // . . . O . . .
+    // X . . O . . .
+    // X X . X . O O
+    // O O X X . X O
+    assert!(status("4227416734141446") == GameStatus::InProgress);
+
+    // End of the bottom row and the start of the row above it
+    // . . . . . . .
+    // X . . . . . .
+    // O O O . X X X
+    assert!(status("7261531") == GameStatus::InProgress);
+}
+
+// Nodes an alpha-beta search of the position takes, without a transposition table so that
+// only the order of the actions makes a difference
+fn nodes(columns: &str, ordering: bool) -> u64 {
+    let game = ConnectFour {};
+    let state = game.parse_state(columns).expect("Invalid moves");
+    let minmax = MinMaxStrategy::new(vec![Box::new(ConnectFourHeuristic {})], 5, true)
+        .with_ordering(Box::new(ConnectFourOrdering {}));
+    minmax.configure(&json!({"ordering": ordering, "ttSize": 0}));
+
+    minmax.select_action(&game, &state);
+    return minmax.search_info().unwrap().nodes;
+}
+
+#[test]
+fn move_ordering_searches_fewer_nodes() {
+    for columns in ["", "44", "4453", "1234567"].iter() {
+        let ordered = nodes(columns, true);
+        let unordered = nodes(columns, false);
+
+        assert!(
+            ordered * 2 < unordered,
+            "{}: {} nodes with ordering, {} without",
+            columns,
+            ordered,
+            unordered
+        );
+    }
+}
@@ -0,0 +1,79 @@
+use board_game_ai::core::{Game, GameStatus};
+use board_game_ai::games::{Go, GoCell};
+use board_game_ai::playground::PlaygroundUtils;
+
+#[test]
+fn ko_recapture_is_refused() {
+    let game = Go::new();
+
+    // White D5 has just taken the black stone on E5 and can be taken back right away
+    let ko = "C5 E6 D6 F5 D4 E4 E5 D5";
+    assert!(game.parse_state(ko).is_some());
+    assert!(game.parse_state(&format!("{} E5", ko)).is_none());
+
+    // Once both sides have played elsewhere the board is new and the recapture is allowed
+    let state = game
+        .parse_state(&format!("{} A1 A9 E5", ko))
+        .expect("Recapture refused after a ko threat");
+    assert!(
+        state.board[4 * 9 + 3] == GoCell::Empty,
+        "D5 wasn't captured"
+    );
+}
+
+#[test]
+fn suicide_is_illegal_unless_it_captures() {
+    let game = Go::new();
+
+    // A1 would have no liberty and takes nothing
+    assert!(game.parse_state("J9 A2 J8 B1").is_some());
+    assert!(game.parse_state("J9 A2 J8 B1 A1").is_none());
+
+    // B1 has no empty neighbor either, but it takes the white stone on A1
+    let state = game
+        .parse_state("A2 A1 J9 C1 J8 B2 B1")
+        .expect("Capturing move refused");
+    assert!(state.board[0] == GoCell::Empty, "A1 wasn't captured");
+    assert!(state.board[1] == GoCell::Black);
+}
+
+#[test]
+fn two_passes_in_a_row_end_the_game() {
+    let game = Go::new();
+
+    let state = game.parse_state("E5 pass").unwrap();
+    assert!(game.status(&state) == GameStatus::InProgress);
+
+    // A stone in between starts the count again
+    let state = game.parse_state("pass E5 pass").unwrap();
+    assert!(game.status(&state) == GameStatus::InProgress);
+
+    let state = game.parse_state("E5 pass pass").unwrap();
+    assert!(game.status(&state) == GameStatus::Player1Win);
+    assert!(game.parse_state("E5 pass pass C3").is_none());
+}
+
+#[test]
+fn area_scoring_counts_stones_territory_and_komi() {
+    // Black walls off columns A to C, white D and E
+    let moves = "C1 D1 C2 D2 C3 D3 C4 D4 C5 D5 pass pass";
+
+    let game = Go::new().with_size(5).with_komi(7.5);
+    let state = game.parse_state(moves).unwrap();
+    assert!(game.score(&state) == 15.0 - 10.0 - 7.5);
+    assert!(game.status(&state) == GameStatus::Player2Win);
+
+    let game = Go::new().with_size(5).with_komi(0.5);
+    let state = game.parse_state(moves).unwrap();
+    assert!(game.status(&state) == GameStatus::Player1Win);
+
+    let game = Go::new().with_size(5).with_komi(5.0);
+    let state = game.parse_state(moves).unwrap();
+    assert!(game.status(&state) == GameStatus::Draw);
+
+    // With the walls unfinished the empty points reach both colors and belong to no one
+    let game = Go::new().with_size(5).with_komi(0.0);
+    let state = game.parse_state("C1 D1 C2 D2 C3 D3 pass pass").unwrap();
+    assert!(game.score(&state) == 0.0);
+    assert!(game.status(&state) == GameStatus::Draw);
+}
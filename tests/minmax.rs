@@ -0,0 +1,68 @@
+use board_game_ai::core::{Game, Strategy};
+use board_game_ai::games::{Chess, ChessHeuristic, ChessState, MnkGame, MnkHeuristic};
+use board_game_ai::playground::PlaygroundUtils;
+use board_game_ai::strategy::MinMaxStrategy;
+
+use chess::Board;
+
+use std::hash::Hash;
+use std::str::FromStr;
+
+// Searches state with and without alpha-beta and checks that pruning changes the node count
+// but not the chosen action
+fn compare<G: Game>(
+    game: &G,
+    state: &G::State,
+    minmax: &MinMaxStrategy<G>,
+    minmax_ab: &MinMaxStrategy<G>,
+) where
+    G::Action: Hash,
+{
+    let action = minmax.select_action(game, state);
+    let nodes = minmax.search_info().unwrap().nodes;
+
+    let action_ab = minmax_ab.select_action(game, state);
+    let nodes_ab = minmax_ab.search_info().unwrap().nodes;
+
+    assert!(action == action_ab, "alpha-beta chose a different action");
+    assert!(
+        nodes_ab < nodes,
+        "alpha-beta searched {} nodes, minimax {}",
+        nodes_ab,
+        nodes
+    );
+}
+
+#[test]
+fn alpha_beta_matches_minmax_tic_tac_toe() {
+    let game = MnkGame::tic_tac_toe();
+    // Moves played from the empty board
+    let positions = ["", "b2", "a1 b2", "a1 b1 b2 c3", "a1 a2 b1 b2"];
+
+    for position in positions.iter() {
+        let minmax = MinMaxStrategy::new(vec![Box::new(MnkHeuristic {})], 10, false);
+        let minmax_ab = MinMaxStrategy::new(vec![Box::new(MnkHeuristic {})], 10, true);
+
+        let state = game.parse_state(position).expect("Invalid moves");
+
+        compare(&game, &state, &minmax, &minmax_ab);
+    }
+}
+
+#[test]
+fn alpha_beta_matches_minmax_chess() {
+    let positions = [
+        "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+        "r1bqkb1r/pppp1ppp/2n2n2/4p2Q/2B1P3/8/PPPP1PPP/RNB1K1NR w KQkq - 4 4",
+        "r3k2r/ppp2ppp/2n1bn2/3qp3/3P4/2N1BN2/PPP2PPP/R2QK2R b KQkq - 0 9",
+        "6k1/5ppp/8/8/8/8/1r3PPP/3R2K1 w - - 0 1",
+    ];
+
+    for position in positions.iter() {
+        let minmax = MinMaxStrategy::new(vec![Box::new(ChessHeuristic {})], 2, false);
+        let minmax_ab = MinMaxStrategy::new(vec![Box::new(ChessHeuristic {})], 2, true);
+        let state = ChessState::new(Board::from_str(position).unwrap());
+
+        compare(&Chess::new(), &state, &minmax, &minmax_ab);
+    }
+}
@@ -0,0 +1,152 @@
+use board_game_ai::core::{Game, GameStatus, Player, Strategy};
+use board_game_ai::games::{
+    Othello, OthelloAction, OthelloHeuristic, OthelloOrdering, OthelloState,
+};
+use board_game_ai::playground::PlaygroundUtils;
+use board_game_ai::strategy::MinMaxStrategy;
+
+use serde_json::json;
+
+fn square(name: &str) -> u8 {
+    let name = name.as_bytes();
+    return (name[1] - b'1') * 8 + (name[0] - b'a');
+}
+
+fn square_name(square: u8) -> String {
+    return format!("{}{}", (b'a' + square % 8) as char, square / 8 + 1);
+}
+
+// Position with discs of black (X) and white (O) on the given squares and to_move, X or O,
+// to move
+fn position(black: &[&str], white: &[&str], to_move: &str) -> OthelloState {
+    let mut squares = vec!['-'; 64];
+    for s in black.iter() {
+        squares[square(s) as usize] = 'X';
+    }
+    for s in white.iter() {
+        squares[square(s) as usize] = 'O';
+    }
+
+    let squares: String = squares.into_iter().collect();
+    return Othello {}
+        .parse_state(&format!("{} {}", squares, to_move))
+        .expect("Invalid position");
+}
+
+// White discs that black turns over by placing a disc on placed
+fn flipped(black: &[&str], white: &[&str], placed: &str) -> Vec<String> {
+    let game = Othello {};
+    let state = position(black, white, "X");
+    let action = OthelloAction::Place(square(placed));
+    assert!(
+        game.actions(&state).contains(&action),
+        "{} is illegal",
+        placed
+    );
+
+    let flipped = state.boards[1] & !game.play(&action, &state).boards[1];
+    let mut names: Vec<String> = (0..64)
+        .filter(|s| flipped & (1 << s) != 0)
+        .map(square_name)
+        .collect();
+    names.sort();
+
+    return names;
+}
+
+#[test]
+fn a_player_without_a_move_has_to_pass() {
+    let game = Othello {};
+
+    // White can't flank the black disc, black can play c1
+    let state = position(&["a1"], &["b1"], "O");
+    assert!(game.actions(&state) == vec![OthelloAction::Pass]);
+    assert!(game.status(&state) == GameStatus::InProgress);
+
+    let state = game.play(&OthelloAction::Pass, &state);
+    assert!(game.player(&state) == Player::Player1);
+    assert!(state.boards == position(&["a1"], &["b1"], "X").boards);
+    assert!(game.actions(&state) == vec![OthelloAction::Place(square("c1"))]);
+}
+
+#[test]
+fn the_game_ends_when_neither_player_can_move() {
+    let game = Othello {};
+
+    // No disc touches one of the other color, the disc count decides
+    let state = position(&["a1", "b1", "c1"], &["g8", "h8"], "X");
+    assert!(game.status(&state) == GameStatus::Player1Win);
+
+    let state = position(&["a1"], &["g8", "h8"], "O");
+    assert!(game.status(&state) == GameStatus::Player2Win);
+
+    let state = position(&["a1", "b1"], &["g8", "h8"], "X");
+    assert!(game.status(&state) == GameStatus::Draw);
+
+    // Taking the last white disc ends the game at once
+    let state = position(&["a1"], &["b1"], "X");
+    let state = game.play(&OthelloAction::Place(square("c1")), &state);
+    assert!(game.status(&state) == GameStatus::Player1Win);
+}
+
+#[test]
+fn discs_flip_in_every_direction() {
+    // From d4 every direction has a line of white discs closed by a black one. Only the
+    // white disc on d7, behind the black one on d6, stays.
+    let black = ["d6", "d2", "g4", "b4", "f6", "b6", "f2", "b2"];
+    let white = ["d5", "d3", "e4", "f4", "c4", "e5", "c5", "e3", "c3", "d7"];
+
+    assert!(
+        flipped(&black, &white, "d4") == vec!["c3", "c4", "c5", "d3", "d5", "e3", "e4", "e5", "f4"]
+    );
+}
+
+// Each placed disc has a white disc on the edge of the board next to it, and past the edge,
+// on the far side of the board, a black disc. Another line makes the move legal.
+#[test]
+fn flips_stop_at_the_board_edges() {
+    // East from the h-file onto the a-file
+    assert!(flipped(&["a5", "g6"], &["h4", "g5"], "g4") == vec!["g5"]);
+    // West from the a-file onto the h-file
+    assert!(flipped(&["h4", "b7"], &["a5", "b6"], "b5") == vec!["b6"]);
+    // North-east
+    assert!(flipped(&["a6", "g5"], &["h4", "g4"], "g3") == vec!["g4"]);
+    // North-west
+    assert!(flipped(&["h4", "b5"], &["a4", "b4"], "b3") == vec!["b4"]);
+    // South-east
+    assert!(flipped(&["a5", "g4"], &["h5", "g5"], "g6") == vec!["g5"]);
+    // South-west
+    assert!(flipped(&["h3", "b4"], &["a5", "b5"], "b6") == vec!["b5"]);
+}
+
+#[test]
+fn transposition_table_does_not_change_the_choice_around_a_pass() {
+    let game = Othello {};
+
+    //   a b c d e f g h
+    // 1 X O O O O . . O
+    // 2 X O O X O . O O
+    // 3 X O O O O O O O
+    // 4 X O X O O X O O
+    // 5 X X O O X O O O
+    // 6 X X O O O O O O
+    // 7 O X . X O O O O
+    // 8 O O O O O O O O
+    // After c7 white has to pass, which leaves the discs as they are with black to move
+    let state = game
+        .parse_state("XOOOO--OXOOXO-OOXOOOOOOOXOXOOXOOXXOOXOOOXXOOOOOOOX-XOOOOOOOOOOOO X")
+        .unwrap();
+    let c7 = game.play(&OthelloAction::Place(square("c7")), &state);
+    assert!(game.actions(&c7) == vec![OthelloAction::Pass]);
+
+    for depth in 1..=8 {
+        let search = |tt_size: u64| {
+            let minmax = MinMaxStrategy::new(vec![Box::new(OthelloHeuristic {})], depth, true)
+                .with_ordering(Box::new(OthelloOrdering {}));
+            minmax.configure(&json!({ "ttSize": tt_size }));
+            return minmax.select_action(&game, &state);
+        };
+
+        assert!(search(1 << 16) == search(0), "depth {}", depth);
+    }
+}
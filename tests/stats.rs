@@ -0,0 +1,78 @@
+use board_game_ai::stats::{Hypothesis, Score, Sprt, Z_95};
+
+fn score(wins: u64, draws: u64, losses: u64) -> Score {
+    return Score {
+        wins: wins,
+        draws: draws,
+        losses: losses,
+    };
+}
+
+#[test]
+fn wilson_interval_stays_within_bounds() {
+    let (low, high) = score(0, 0, 4).wilson_interval(Z_95);
+    assert!(low == 0.0 && low.is_sign_positive(), "low bound {}", low);
+    assert!((high - 0.490).abs() < 1e-3, "high bound {}", high);
+
+    let (low, high) = score(4, 0, 0).wilson_interval(Z_95);
+    assert!((low - 0.510).abs() < 1e-3, "low bound {}", low);
+    assert!(high == 1.0, "high bound {}", high);
+}
+
+#[test]
+fn elo_interval_of_perfect_score_is_bounded_below() {
+    let (low, high) = score(4, 0, 0).elo_interval(Z_95);
+    assert!(low.is_finite() && low > 0.0, "low bound {}", low);
+    assert!(high == f64::INFINITY, "high bound {}", high);
+
+    let (low, high) = score(0, 0, 4).elo_interval(Z_95);
+    assert!(low == f64::NEG_INFINITY, "low bound {}", low);
+    assert!(high.is_finite() && high < 0.0, "high bound {}", high);
+
+    let (low, high) = score(5, 10, 5).elo_interval(Z_95);
+    assert!(low < 0.0 && high > 0.0 && (low + high).abs() < 1e-6);
+}
+
+#[test]
+fn sprt_llr_matches_known_values() {
+    let sprt = Sprt::new(0.0, 10.0, 0.05, 0.05);
+    let cases = [
+        (score(60, 20, 20), 1.699_901_653),
+        (score(20, 20, 60), -1.828_722_841),
+        (score(10, 80, 10), -0.200_091_366),
+        (score(0, 0, 0), 0.0),
+    ];
+
+    for (score, expected) in cases.iter() {
+        let llr = sprt.llr(score);
+        assert!(
+            (llr - expected).abs() < 1e-6,
+            "llr {}, expected {}",
+            llr,
+            expected
+        );
+    }
+
+    // Scoring exactly halfway between the hypotheses favours neither
+    let sprt = Sprt::new(-5.0, 5.0, 0.05, 0.05);
+    assert!(sprt.llr(&score(30, 40, 30)).abs() < 1e-12);
+}
+
+#[test]
+fn sprt_decides_once_llr_crosses_a_bound() {
+    let sprt = Sprt::new(0.0, 20.0, 0.05, 0.05);
+    let (lower, upper) = sprt.bounds();
+    assert!((lower + 19f64.ln()).abs() < 1e-12 && (upper - 19f64.ln()).abs() < 1e-12);
+
+    // The 92nd win takes the llr from just below the upper bound to just above it
+    assert!(sprt.llr(&score(91, 50, 50)) < upper);
+    assert!(sprt.decide(&score(91, 50, 50)).is_none());
+    assert!(sprt.llr(&score(92, 50, 50)) >= upper);
+    assert!(sprt.decide(&score(92, 50, 50)) == Some(Hypothesis::H1));
+
+    // And the 81st loss past the lower one
+    assert!(sprt.llr(&score(50, 50, 80)) > lower);
+    assert!(sprt.decide(&score(50, 50, 80)).is_none());
+    assert!(sprt.llr(&score(50, 50, 81)) <= lower);
+    assert!(sprt.decide(&score(50, 50, 81)) == Some(Hypothesis::H0));
+}
@@ -0,0 +1,148 @@
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time;
+
+struct Engine {
+    child: Child,
+    stdin: ChildStdin,
+    lines: mpsc::Receiver<String>,
+}
+
+impl Engine {
+    fn new() -> Engine {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_uci_engine"))
+            .args(&["MinMaxAB"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("Failed to start engine");
+        let stdin = child.stdin.take().unwrap();
+        let stdout = child.stdout.take().unwrap();
+
+        // Read on another thread so that a silent engine fails the test instead of hanging it
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                if sender.send(line.expect("Failed to read line")).is_err() {
+                    return;
+                }
+            }
+        });
+
+        let mut engine = Engine {
+            child: child,
+            stdin: stdin,
+            lines: lines,
+        };
+        engine.send("uci");
+        engine.read_until("uciok", 5);
+
+        return engine;
+    }
+
+    fn send(&mut self, command: &str) {
+        writeln!(self.stdin, "{}", command).expect("Failed to write command");
+    }
+
+    // Lines printed up to and including the first one starting with prefix
+    fn read_until(&mut self, prefix: &str, timeout_secs: u64) -> Vec<String> {
+        let deadline = time::Instant::now() + time::Duration::from_secs(timeout_secs);
+        let mut lines = Vec::new();
+
+        loop {
+            let timeout = deadline.saturating_duration_since(time::Instant::now());
+            let line = match self.lines.recv_timeout(timeout) {
+                Ok(line) => line,
+                Err(_) => panic!("No {} within {}s after {:?}", prefix, timeout_secs, lines),
+            };
+            let done = line.starts_with(prefix);
+            lines.push(line);
+
+            if done {
+                return lines;
+            }
+        }
+    }
+
+    fn best_move(&mut self, timeout_secs: u64) -> (Vec<String>, String) {
+        let lines = self.read_until("bestmove", timeout_secs);
+        let bestmove = lines
+            .last()
+            .unwrap()
+            .split_whitespace()
+            .nth(1)
+            .unwrap()
+            .to_string();
+        return (lines, bestmove);
+    }
+}
+
+impl Drop for Engine {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+    }
+}
+
+#[test]
+fn engine_accepts_its_own_best_moves() {
+    let mut engine = Engine::new();
+
+    // The only sensible move is a promotion, which is sent in lowercase
+    let fen = "8/4P3/8/8/8/8/k7/4K3 w - - 0 1";
+    engine.send(&format!("position fen {}", fen));
+    engine.send("go depth 2");
+    let (_, promotion) = engine.best_move(30);
+    assert_eq!(promotion, "e7e8q");
+
+    engine.send(&format!("position fen {} moves {}", fen, promotion));
+    engine.send("go depth 1");
+    let (lines, reply) = engine.best_move(30);
+    assert!(
+        lines.iter().all(|l| !l.contains("illegal move")),
+        "{:?}",
+        lines
+    );
+
+    // Both moves played through position have to leave a legal position for the next search
+    engine.send(&format!(
+        "position fen {} moves {} {}",
+        fen, promotion, reply
+    ));
+    engine.send("isready");
+    let lines = engine.read_until("readyok", 5);
+    assert!(
+        lines.iter().all(|l| !l.contains("illegal move")),
+        "{:?}",
+        lines
+    );
+}
+
+#[test]
+fn engine_searches_exactly_the_requested_depth() {
+    let mut engine = Engine::new();
+
+    for depth in 1..=3 {
+        engine.send("position startpos");
+        engine.send(&format!("go depth {}", depth));
+        let (lines, _) = engine.best_move(60);
+        let info = format!("info depth {} ", depth);
+        assert!(lines.iter().any(|l| l.starts_with(&info)), "{:?}", lines);
+    }
+}
+
+#[test]
+fn engine_stops_an_infinite_search() {
+    let mut engine = Engine::new();
+
+    engine.send("position startpos");
+    engine.send("go infinite");
+    thread::sleep(time::Duration::from_millis(500));
+
+    // The best move only comes after "stop", and shortly after it
+    assert!(engine.lines.try_recv().is_err());
+    engine.send("stop");
+    let (_, bestmove) = engine.best_move(5);
+    assert_eq!(bestmove.len(), 4);
+}